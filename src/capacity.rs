@@ -0,0 +1,84 @@
+use crate::{ArcKind, NodeRef, PetriError, PetriNet, PlaceRef, Result};
+use std::collections::HashMap;
+use std::convert::TryFrom;
+
+impl PetriNet {
+    /// true if at least one place has a capacity
+    pub fn has_capacities(&self) -> bool {
+        self.places
+            .iter()
+            .flatten()
+            .any(|place| place.capacity.is_some())
+    }
+
+    /// Compiles the capacities of the net away. Every place `p` with capacity
+    /// `k` gets a complementary place that holds `k` minus the tokens of `p`.
+    /// Transitions that add tokens to `p` in total consume the same amount
+    /// from the complement, transitions that remove tokens produce them on
+    /// the complement, so the net behaves as under the strict firing rule.
+    /// The complements are named after their place with a `_complement`
+    /// suffix. Returns the complement of every place that had a capacity.
+    ///
+    /// Fails with `UnsupportedFeature` if a place with a capacity is emptied
+    /// by a reset arc, the complement would have to be refilled, and with
+    /// `DuplicateName` if names have to be unique and a complement name is
+    /// taken. The net is left unchanged in both cases.
+    pub fn complement_capacities(&mut self) -> Result<HashMap<PlaceRef, PlaceRef>> {
+        let bounded: Vec<(PlaceRef, usize)> = self
+            .places()
+            .filter_map(|place| {
+                let capacity = self.place(place).ok()?.capacity?;
+                Some((place, capacity))
+            })
+            .collect();
+        for (place, _) in &bounded {
+            let special = &self.place(*place)?.special;
+            if special.keys().any(|(_, kind)| *kind == ArcKind::Reset) {
+                return Err(PetriError::UnsupportedFeature(
+                    "complementary places of places with reset arcs".into(),
+                ));
+            }
+            if self.unique_names {
+                if let Some(name) = &self.place(*place)?.name {
+                    let complement = complement_name(name);
+                    if self.names.contains_key(&complement) {
+                        return Err(PetriError::DuplicateName(complement));
+                    }
+                }
+            }
+        }
+        let mut complements = HashMap::new();
+        for (place, capacity) in bounded {
+            let (marking, name) = {
+                let data = self.place(place)?;
+                (data.marking, data.name.clone())
+            };
+            let complement = self.add_place();
+            if let Some(name) = name {
+                complement.name(self, complement_name(&name))?;
+            }
+            let complement_ref = PlaceRef::try_from(complement)?;
+            complement_ref.marking(self, capacity - marking)?;
+            let produced = place.preset(self)?;
+            let consumed = place.postset(self)?;
+            let transitions: Vec<_> = produced.keys().chain(consumed.keys()).collect();
+            for transition in transitions {
+                let added = *produced.get(transition).unwrap_or(&0) as i64;
+                let removed = *consumed.get(transition).unwrap_or(&0) as i64;
+                let transition = NodeRef::Transition(*transition);
+                if added > removed {
+                    self.add_weighted_arc(complement, transition, (added - removed) as usize)?;
+                } else if removed > added {
+                    self.add_weighted_arc(transition, complement, (removed - added) as usize)?;
+                }
+            }
+            place.capacity(self, None)?;
+            complements.insert(place, complement_ref);
+        }
+        Ok(complements)
+    }
+}
+
+fn complement_name(name: &str) -> String {
+    format!("{}_complement", name)
+}
@@ -0,0 +1,718 @@
+use crate::{NodeRef, PetriError, PetriNet, PlaceRef, Result};
+use std::collections::{BTreeMap, HashMap};
+use std::convert::TryFrom;
+
+/// Value of a token in a coloured net.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Colour {
+    /// the only value of the `Dot` sort, a plain black token
+    Dot,
+    /// element of an enumeration
+    Constant(String),
+    Integer(i64),
+    /// element of a product sort
+    Tuple(Vec<Colour>),
+}
+
+impl std::fmt::Display for Colour {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Colour::Dot => write!(f, "dot"),
+            Colour::Constant(constant) => write!(f, "{}", constant),
+            Colour::Integer(integer) => write!(f, "{}", integer),
+            Colour::Tuple(components) => {
+                write!(f, "(")?;
+                for (index, component) in components.iter().enumerate() {
+                    if index > 0 {
+                        write!(f, ",")?;
+                    }
+                    write!(f, "{}", component)?;
+                }
+                write!(f, ")")
+            }
+        }
+    }
+}
+
+/// Finite set of colours, the type of a place or variable.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Sort {
+    Dot,
+    /// constants in the order of their declaration
+    Enumeration(Vec<String>),
+    /// all integers from `start` to `end` (inclusive)
+    Range {
+        start: i64,
+        end: i64,
+    },
+    /// tuples with one colour of every component
+    Product(Vec<SortRef>),
+}
+
+/// Single colour that depends on the binding of the variables.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Term {
+    Variable(VariableRef),
+    Constant(Colour),
+    /// next colour in the cyclic order of the sort
+    Successor(Box<Term>),
+    /// previous colour in the cyclic order of the sort
+    Predecessor(Box<Term>),
+    /// colour of a product sort
+    Tuple(Vec<Term>),
+}
+
+/// Multiset of colours that an arc consumes or produces.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ArcExpression {
+    /// one token of the colour of the term
+    Term(Term),
+    /// `n` times the tokens of the expression
+    Scaled(usize, Box<ArcExpression>),
+    /// one token of every colour of the sort of the place
+    All,
+    Sum(Vec<ArcExpression>),
+}
+
+/// Condition on the binding of the variables of a transition.
+/// Comparisons use the order of the colours in their sort.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Guard {
+    True,
+    Not(Box<Guard>),
+    And(Vec<Guard>),
+    Or(Vec<Guard>),
+    Equal(Term, Term),
+    NotEqual(Term, Term),
+    LessThan(Term, Term),
+    LessThanOrEqual(Term, Term),
+    GreaterThan(Term, Term),
+    GreaterThanOrEqual(Term, Term),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct SortRef {
+    pub(crate) index: usize,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct VariableRef {
+    pub(crate) index: usize,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct ColouredPlaceRef {
+    pub(crate) index: usize,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct ColouredTransitionRef {
+    pub(crate) index: usize,
+}
+
+#[derive(Debug, Clone)]
+pub(crate) struct ColouredPlace {
+    pub name: String,
+    pub sort: SortRef,
+    pub marking: BTreeMap<Colour, usize>,
+}
+
+#[derive(Debug, Clone)]
+pub(crate) struct ColouredTransition {
+    pub name: String,
+    pub guard: Guard,
+}
+
+#[derive(Debug, Clone)]
+pub(crate) struct ColouredArc {
+    pub place: ColouredPlaceRef,
+    pub transition: ColouredTransitionRef,
+    /// true for arcs from the transition to the place
+    pub produce: bool,
+    pub expression: ArcExpression,
+}
+
+/// High level net whose places hold coloured tokens. Transitions fire in a
+/// binding of their variables that satisfies the guard. `unfold` translates
+/// it into an equivalent `PetriNet`.
+#[derive(Debug, Clone, Default)]
+pub struct ColouredNet {
+    pub(crate) sorts: Vec<(String, Sort)>,
+    pub(crate) variables: Vec<(String, SortRef)>,
+    pub(crate) places: Vec<ColouredPlace>,
+    pub(crate) transitions: Vec<ColouredTransition>,
+    pub(crate) arcs: Vec<ColouredArc>,
+}
+
+impl ColouredNet {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Fails with `InvalidData` for sorts without colours.
+    pub fn add_sort(&mut self, name: String, sort: Sort) -> Result<SortRef> {
+        if self.sort_size(&sort)? == 0 {
+            return Err(PetriError::InvalidData(format!("sort {} is empty", name)));
+        }
+        self.sorts.push((name, sort));
+        Ok(SortRef {
+            index: self.sorts.len() - 1,
+        })
+    }
+
+    pub fn add_variable(&mut self, name: String, sort: SortRef) -> Result<VariableRef> {
+        self.sort(sort)?;
+        self.variables.push((name, sort));
+        Ok(VariableRef {
+            index: self.variables.len() - 1,
+        })
+    }
+
+    pub fn add_place(&mut self, name: String, sort: SortRef) -> Result<ColouredPlaceRef> {
+        self.sort(sort)?;
+        self.places.push(ColouredPlace {
+            name,
+            sort,
+            marking: BTreeMap::new(),
+        });
+        Ok(ColouredPlaceRef {
+            index: self.places.len() - 1,
+        })
+    }
+
+    /// new transitions have no guard (`Guard::True`)
+    pub fn add_transition(&mut self, name: String) -> ColouredTransitionRef {
+        self.transitions.push(ColouredTransition {
+            name,
+            guard: Guard::True,
+        });
+        ColouredTransitionRef {
+            index: self.transitions.len() - 1,
+        }
+    }
+
+    /// arc that consumes the tokens of `expression` from `place`
+    pub fn add_input_arc(
+        &mut self,
+        place: ColouredPlaceRef,
+        transition: ColouredTransitionRef,
+        expression: ArcExpression,
+    ) -> Result<()> {
+        self.add_arc(place, transition, false, expression)
+    }
+
+    /// arc that produces the tokens of `expression` on `place`
+    pub fn add_output_arc(
+        &mut self,
+        transition: ColouredTransitionRef,
+        place: ColouredPlaceRef,
+        expression: ArcExpression,
+    ) -> Result<()> {
+        self.add_arc(place, transition, true, expression)
+    }
+
+    fn add_arc(
+        &mut self,
+        place: ColouredPlaceRef,
+        transition: ColouredTransitionRef,
+        produce: bool,
+        expression: ArcExpression,
+    ) -> Result<()> {
+        self.place(place)?;
+        self.transition(transition)?;
+        self.arcs.push(ColouredArc {
+            place,
+            transition,
+            produce,
+            expression,
+        });
+        Ok(())
+    }
+
+    pub(crate) fn sort(&self, sort: SortRef) -> Result<&Sort> {
+        self.sorts
+            .get(sort.index)
+            .map(|(_, sort)| sort)
+            .ok_or(PetriError::ObjectNotFound)
+    }
+
+    pub(crate) fn place(&self, place: ColouredPlaceRef) -> Result<&ColouredPlace> {
+        self.places
+            .get(place.index)
+            .ok_or(PetriError::PlaceNotFound)
+    }
+
+    fn place_mut(&mut self, place: ColouredPlaceRef) -> Result<&mut ColouredPlace> {
+        self.places
+            .get_mut(place.index)
+            .ok_or(PetriError::PlaceNotFound)
+    }
+
+    pub(crate) fn transition(
+        &self,
+        transition: ColouredTransitionRef,
+    ) -> Result<&ColouredTransition> {
+        self.transitions
+            .get(transition.index)
+            .ok_or(PetriError::TransitionNotFound)
+    }
+
+    /// all colours of the sort in ascending order
+    pub fn colours(&self, sort: SortRef) -> Result<Vec<Colour>> {
+        self.sort_colours(self.sort(sort)?)
+    }
+
+    fn sort_colours(&self, sort: &Sort) -> Result<Vec<Colour>> {
+        Ok(match sort {
+            Sort::Dot => vec![Colour::Dot],
+            Sort::Enumeration(constants) => constants
+                .iter()
+                .map(|constant| Colour::Constant(constant.clone()))
+                .collect(),
+            Sort::Range { start, end } => (*start..=*end).map(Colour::Integer).collect(),
+            Sort::Product(components) => {
+                let mut tuples = vec![Vec::new()];
+                for component in components {
+                    let colours = self.colours(*component)?;
+                    tuples = tuples
+                        .into_iter()
+                        .flat_map(|tuple: Vec<Colour>| {
+                            colours.iter().map(move |colour| {
+                                let mut tuple = tuple.clone();
+                                tuple.push(colour.clone());
+                                tuple
+                            })
+                        })
+                        .collect();
+                }
+                tuples.into_iter().map(Colour::Tuple).collect()
+            }
+        })
+    }
+
+    /// number of colours of the sort, without listing them
+    fn sort_size(&self, sort: &Sort) -> Result<usize> {
+        let too_large = || PetriError::InvalidData("sort has too many colours".into());
+        match sort {
+            Sort::Dot => Ok(1),
+            Sort::Enumeration(constants) => Ok(constants.len()),
+            Sort::Range { start, end } if start > end => Ok(0),
+            Sort::Range { start, end } => {
+                usize::try_from(i128::from(*end) - i128::from(*start) + 1).map_err(|_| too_large())
+            }
+            Sort::Product(components) => components.iter().try_fold(1usize, |size, component| {
+                size.checked_mul(self.sort_size(self.sort(*component)?)?)
+                    .ok_or_else(too_large)
+            }),
+        }
+    }
+
+    /// index of the colour in the ascending order of the sort
+    pub(crate) fn position(&self, sort: &Sort, colour: &Colour) -> Option<usize> {
+        match (sort, colour) {
+            (Sort::Dot, Colour::Dot) => Some(0),
+            (Sort::Enumeration(constants), Colour::Constant(constant)) => {
+                constants.iter().position(|other| other == constant)
+            }
+            (Sort::Range { start, end }, Colour::Integer(integer))
+                if start <= integer && integer <= end =>
+            {
+                Some((integer - start) as usize)
+            }
+            (Sort::Product(sorts), Colour::Tuple(components))
+                if sorts.len() == components.len() =>
+            {
+                let mut position = 0;
+                for (sort, component) in sorts.iter().zip(components) {
+                    let sort = self.sort(*sort).ok()?;
+                    let size = self.sort_size(sort).ok()?;
+                    position = position * size + self.position(sort, component)?;
+                }
+                Some(position)
+            }
+            _ => None,
+        }
+    }
+
+    /// neighbour of a colour in the cyclic order of the sort
+    fn shift(&self, sort: &Sort, colour: &Colour, forward: bool) -> Result<Colour> {
+        if let Sort::Product(_) = sort {
+            return Err(PetriError::InvalidData(
+                "successors are not defined for product sorts".into(),
+            ));
+        }
+        let size = self.sort_size(sort)?;
+        let position = self.position(sort, colour).ok_or_else(|| {
+            PetriError::InvalidData(format!("colour {} is not part of the sort", colour))
+        })?;
+        let next = if forward {
+            (position + 1) % size
+        } else {
+            (position + size - 1) % size
+        };
+        Ok(match sort {
+            Sort::Enumeration(constants) => Colour::Constant(constants[next].clone()),
+            Sort::Range { start, .. } => Colour::Integer(start + next as i64),
+            _ => Colour::Dot,
+        })
+    }
+
+    fn variable_sort(&self, variable: VariableRef) -> Result<&Sort> {
+        let (_, sort) = self
+            .variables
+            .get(variable.index)
+            .ok_or(PetriError::ObjectNotFound)?;
+        self.sort(*sort)
+    }
+
+    /// Translates the net into a P/T net with one place per place and colour
+    /// and one transition per transition and binding that satisfies the
+    /// guard. Places are named `place_colour`, transitions are named after
+    /// the transition followed by the values of their variables. Exporters
+    /// use these names as identifiers.
+    /// Fails with `InvalidData` if an expression produces colours outside the
+    /// sort of its place or uses a variable that is not part of the binding,
+    /// and if a guard compares constants whose order is not given by a sort.
+    pub fn unfold(&self) -> Result<PetriNet> {
+        let mut net = PetriNet::new();
+        net.set_name_ids(true);
+        let mut places: HashMap<(ColouredPlaceRef, Colour), NodeRef> = HashMap::new();
+        for (index, place) in self.places.iter().enumerate() {
+            for colour in self.colours(place.sort)? {
+                let node = net.add_place();
+                node.name(&mut net, format!("{}_{}", place.name, name_part(&colour)))?;
+                let tokens = place.marking.get(&colour).copied().unwrap_or(0);
+                PlaceRef::try_from(node)?.marking(&mut net, tokens)?;
+                places.insert((ColouredPlaceRef { index }, colour), node);
+            }
+        }
+        for (index, transition) in self.transitions.iter().enumerate() {
+            let transition_ref = ColouredTransitionRef { index };
+            let arcs: Vec<&ColouredArc> = self
+                .arcs
+                .iter()
+                .filter(|arc| arc.transition == transition_ref)
+                .collect();
+            let mut variables = Vec::new();
+            guard_variables(&transition.guard, &mut variables);
+            for arc in &arcs {
+                expression_variables(&arc.expression, &mut variables);
+            }
+            variables.sort();
+            variables.dedup();
+            for binding in self.bindings(&variables)? {
+                if !self.satisfies(&transition.guard, &binding)? {
+                    continue;
+                }
+                let node = net.add_transition();
+                let name = variables
+                    .iter()
+                    .fold(transition.name.clone(), |name, variable| {
+                        format!("{}_{}", name, name_part(&binding[variable]))
+                    });
+                node.name(&mut net, name)?;
+                for arc in &arcs {
+                    let sort = self.sort(self.place(arc.place)?.sort)?;
+                    let mut tokens = BTreeMap::new();
+                    self.evaluate_expression(&arc.expression, &binding, sort, 1, &mut tokens)?;
+                    for (colour, count) in tokens {
+                        let place = places[&(arc.place, colour)];
+                        if arc.produce {
+                            net.add_weighted_arc(node, place, count)?;
+                        } else {
+                            net.add_weighted_arc(place, node, count)?;
+                        }
+                    }
+                }
+            }
+        }
+        Ok(net)
+    }
+
+    /// tokens of an expression without variables, like an initial marking
+    pub(crate) fn evaluate_marking(
+        &self,
+        expression: &ArcExpression,
+        sort: SortRef,
+    ) -> Result<Vec<(Colour, usize)>> {
+        let mut tokens = BTreeMap::new();
+        self.evaluate_expression(
+            expression,
+            &HashMap::new(),
+            self.sort(sort)?,
+            1,
+            &mut tokens,
+        )?;
+        Ok(tokens.into_iter().collect())
+    }
+
+    /// all combinations of colours of the variables
+    fn bindings(&self, variables: &[VariableRef]) -> Result<Vec<HashMap<VariableRef, Colour>>> {
+        let mut bindings = vec![HashMap::new()];
+        for variable in variables {
+            let colours = self.sort_colours(self.variable_sort(*variable)?)?;
+            bindings = bindings
+                .into_iter()
+                .flat_map(|binding| {
+                    colours.iter().map(move |colour| {
+                        let mut binding = binding.clone();
+                        binding.insert(*variable, colour.clone());
+                        binding
+                    })
+                })
+                .collect();
+        }
+        Ok(bindings)
+    }
+
+    fn evaluate_expression(
+        &self,
+        expression: &ArcExpression,
+        binding: &HashMap<VariableRef, Colour>,
+        sort: &Sort,
+        factor: usize,
+        tokens: &mut BTreeMap<Colour, usize>,
+    ) -> Result<()> {
+        match expression {
+            ArcExpression::Term(term) => {
+                let colour = self.evaluate(term, binding, Some(sort))?;
+                if self.position(sort, &colour).is_none() {
+                    return Err(PetriError::InvalidData(format!(
+                        "colour {} is not part of the sort of the place",
+                        colour
+                    )));
+                }
+                // zero tokens are no tokens, the colour is still checked
+                if factor > 0 {
+                    *tokens.entry(colour).or_insert(0) += factor;
+                }
+            }
+            ArcExpression::Scaled(count, expression) => {
+                self.evaluate_expression(expression, binding, sort, factor * count, tokens)?
+            }
+            ArcExpression::All if factor == 0 => {}
+            ArcExpression::All => {
+                for colour in self.sort_colours(sort)? {
+                    *tokens.entry(colour).or_insert(0) += factor;
+                }
+            }
+            ArcExpression::Sum(expressions) => {
+                for expression in expressions {
+                    self.evaluate_expression(expression, binding, sort, factor, tokens)?;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// `sort` is needed for successors of constants, variables bring their own
+    fn evaluate(
+        &self,
+        term: &Term,
+        binding: &HashMap<VariableRef, Colour>,
+        sort: Option<&Sort>,
+    ) -> Result<Colour> {
+        match term {
+            Term::Variable(variable) => binding.get(variable).cloned().ok_or_else(|| {
+                PetriError::InvalidData(format!("variable {} is not bound", variable.index))
+            }),
+            Term::Constant(colour) => Ok(colour.clone()),
+            Term::Successor(inner) | Term::Predecessor(inner) => {
+                let sort = match self.term_sort(inner)? {
+                    Some(sort) => self.sort(sort)?,
+                    None => sort.ok_or_else(|| {
+                        PetriError::InvalidData("the sort of a successor is unknown".into())
+                    })?,
+                };
+                let colour = self.evaluate(inner, binding, Some(sort))?;
+                self.shift(sort, &colour, matches!(term, Term::Successor(_)))
+            }
+            Term::Tuple(terms) => {
+                let sorts = match sort {
+                    Some(Sort::Product(sorts)) if sorts.len() == terms.len() => sorts
+                        .iter()
+                        .map(|sort| self.sort(*sort).map(Some))
+                        .collect::<Result<_>>()?,
+                    _ => vec![None; terms.len()],
+                };
+                let components = terms
+                    .iter()
+                    .zip(sorts)
+                    .map(|(term, sort)| self.evaluate(term, binding, sort))
+                    .collect::<Result<_>>()?;
+                Ok(Colour::Tuple(components))
+            }
+        }
+    }
+
+    /// sort of the variable in a term, `None` for constants and tuples
+    pub(crate) fn term_sort(&self, term: &Term) -> Result<Option<SortRef>> {
+        match term {
+            Term::Variable(variable) => self
+                .variables
+                .get(variable.index)
+                .map(|(_, sort)| Some(*sort))
+                .ok_or(PetriError::ObjectNotFound),
+            Term::Constant(_) | Term::Tuple(_) => Ok(None),
+            Term::Successor(inner) | Term::Predecessor(inner) => self.term_sort(inner),
+        }
+    }
+
+    fn satisfies(&self, guard: &Guard, binding: &HashMap<VariableRef, Colour>) -> Result<bool> {
+        use std::cmp::Ordering::*;
+        let compare = |a: &Term, b: &Term| -> Result<std::cmp::Ordering> {
+            let sort = match self.term_sort(a)?.or(self.term_sort(b)?) {
+                Some(sort) => Some(self.sort(sort)?),
+                None => None,
+            };
+            let a = self.evaluate(a, binding, sort)?;
+            let b = self.evaluate(b, binding, sort)?;
+            match sort {
+                Some(sort) => {
+                    let position = |colour: &Colour| {
+                        self.position(sort, colour).ok_or_else(|| {
+                            PetriError::InvalidData(format!(
+                                "colour {} is not part of the compared sort",
+                                colour
+                            ))
+                        })
+                    };
+                    Ok(position(&a)?.cmp(&position(&b)?))
+                }
+                None if a == b => Ok(Equal),
+                // constants carry no sort, all sorts that contain both have
+                // to agree on their order
+                None => {
+                    let mut orders = self.sorts.iter().filter_map(|(_, sort)| {
+                        Some(self.position(sort, &a)?.cmp(&self.position(sort, &b)?))
+                    });
+                    let order = orders.next().ok_or_else(|| {
+                        PetriError::InvalidData(format!("no sort contains both {} and {}", a, b))
+                    })?;
+                    if orders.any(|other| other != order) {
+                        return Err(PetriError::InvalidData(format!(
+                            "the order of {} and {} depends on their sort",
+                            a, b
+                        )));
+                    }
+                    Ok(order)
+                }
+            }
+        };
+        Ok(match guard {
+            Guard::True => true,
+            Guard::Not(guard) => !self.satisfies(guard, binding)?,
+            Guard::And(guards) => {
+                for guard in guards {
+                    if !self.satisfies(guard, binding)? {
+                        return Ok(false);
+                    }
+                }
+                true
+            }
+            Guard::Or(guards) => {
+                for guard in guards {
+                    if self.satisfies(guard, binding)? {
+                        return Ok(true);
+                    }
+                }
+                false
+            }
+            Guard::Equal(a, b) => compare(a, b)? == Equal,
+            Guard::NotEqual(a, b) => compare(a, b)? != Equal,
+            Guard::LessThan(a, b) => compare(a, b)? == Less,
+            Guard::LessThanOrEqual(a, b) => compare(a, b)? != Greater,
+            Guard::GreaterThan(a, b) => compare(a, b)? == Greater,
+            Guard::GreaterThanOrEqual(a, b) => compare(a, b)? != Less,
+        })
+    }
+}
+
+impl ColouredPlaceRef {
+    /// Initial tokens of the place as (colour, count) pairs.
+    /// Fails with `InvalidData` for colours outside the sort of the place.
+    pub fn marking(self, net: &mut ColouredNet, marking: Vec<(Colour, usize)>) -> Result<()> {
+        let sort = net.sort(net.place(self)?.sort)?;
+        let mut tokens = BTreeMap::new();
+        for (colour, count) in marking {
+            if net.position(sort, &colour).is_none() {
+                return Err(PetriError::InvalidData(format!(
+                    "colour {} is not part of the sort of the place",
+                    colour
+                )));
+            }
+            *tokens.entry(colour).or_insert(0) += count;
+        }
+        net.place_mut(self)?.marking = tokens;
+        Ok(())
+    }
+}
+
+impl ColouredTransitionRef {
+    pub fn guard(self, net: &mut ColouredNet, guard: Guard) -> Result<()> {
+        net.transitions
+            .get_mut(self.index)
+            .ok_or(PetriError::TransitionNotFound)?
+            .guard = guard;
+        Ok(())
+    }
+}
+
+fn term_variables(term: &Term, variables: &mut Vec<VariableRef>) {
+    match term {
+        Term::Variable(variable) => variables.push(*variable),
+        Term::Constant(_) => {}
+        Term::Successor(inner) | Term::Predecessor(inner) => term_variables(inner, variables),
+        Term::Tuple(terms) => {
+            for term in terms {
+                term_variables(term, variables);
+            }
+        }
+    }
+}
+
+/// colours as part of node names, tuples are joined by underscores
+fn name_part(colour: &Colour) -> String {
+    match colour {
+        Colour::Tuple(components) => components
+            .iter()
+            .map(name_part)
+            .collect::<Vec<_>>()
+            .join("_"),
+        colour => colour.to_string(),
+    }
+}
+
+fn expression_variables(expression: &ArcExpression, variables: &mut Vec<VariableRef>) {
+    match expression {
+        ArcExpression::Term(term) => term_variables(term, variables),
+        ArcExpression::Scaled(_, expression) => expression_variables(expression, variables),
+        ArcExpression::All => {}
+        ArcExpression::Sum(expressions) => {
+            for expression in expressions {
+                expression_variables(expression, variables);
+            }
+        }
+    }
+}
+
+fn guard_variables(guard: &Guard, variables: &mut Vec<VariableRef>) {
+    match guard {
+        Guard::True => {}
+        Guard::Not(guard) => guard_variables(guard, variables),
+        Guard::And(guards) | Guard::Or(guards) => {
+            for guard in guards {
+                guard_variables(guard, variables);
+            }
+        }
+        Guard::Equal(a, b)
+        | Guard::NotEqual(a, b)
+        | Guard::LessThan(a, b)
+        | Guard::LessThanOrEqual(a, b)
+        | Guard::GreaterThan(a, b)
+        | Guard::GreaterThanOrEqual(a, b) => {
+            term_variables(a, variables);
+            term_variables(b, variables);
+        }
+    }
+}
@@ -0,0 +1,231 @@
+use crate::{ArcKind, PetriError, PetriNet, PlaceRef, Result, TransitionRef};
+use std::collections::HashSet;
+
+/// Token count of a place in a coverability tree. `Omega` stands for
+/// arbitrarily many tokens.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Tokens {
+    Finite(usize),
+    Omega,
+}
+
+impl Tokens {
+    fn covers(self, tokens: usize) -> bool {
+        match self {
+            Tokens::Finite(own) => own >= tokens,
+            Tokens::Omega => true,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct OmegaMarking {
+    tokens: Vec<Tokens>,
+}
+
+impl OmegaMarking {
+    pub fn tokens(&self, place: PlaceRef) -> Tokens {
+        self.tokens
+            .get(place.index)
+            .copied()
+            .unwrap_or(Tokens::Finite(0))
+    }
+
+    /// true if every place holds at least as many tokens as in `other`
+    pub fn covers(&self, other: &OmegaMarking) -> bool {
+        self.tokens
+            .iter()
+            .zip(other.tokens.iter())
+            .all(|(own, other)| match other {
+                Tokens::Finite(tokens) => own.covers(*tokens),
+                Tokens::Omega => *own == Tokens::Omega,
+            })
+    }
+}
+
+/// Firing sequence that shows that a place is unbounded: after `prefix`,
+/// every repetition of `pump` leaves more tokens on the place.
+/// If `pump` needs tokens from other unbounded places, their pumps have to be
+/// repeated often enough before.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UnboundedWitness {
+    pub prefix: Vec<TransitionRef>,
+    pub pump: Vec<TransitionRef>,
+}
+
+#[derive(Debug, Clone)]
+struct CoverabilityNode {
+    marking: OmegaMarking,
+    /// parent node and the transition that leads from there to this node
+    parent: Option<(usize, TransitionRef)>,
+    /// places that became omega in this node and the ancestor they were accelerated from
+    accelerated: Vec<(usize, usize)>,
+}
+
+/// Karp-Miller coverability tree: a finite abstraction of the reachable
+/// markings in which places that can grow without bound are marked with omega.
+#[derive(Debug, Clone)]
+pub struct CoverabilityTree {
+    nodes: Vec<CoverabilityNode>,
+    places: Vec<PlaceRef>,
+}
+
+impl CoverabilityTree {
+    pub fn markings(&self) -> impl Iterator<Item = &OmegaMarking> {
+        self.nodes.iter().map(|node| &node.marking)
+    }
+
+    pub fn is_bounded(&self) -> bool {
+        self.nodes
+            .iter()
+            .all(|node| !node.marking.tokens.contains(&Tokens::Omega))
+    }
+
+    /// maximal number of tokens on the place, `None` if it is unbounded
+    pub fn bound(&self, place: PlaceRef) -> Option<usize> {
+        let mut bound = 0;
+        for node in &self.nodes {
+            match node.marking.tokens(place) {
+                Tokens::Finite(tokens) => bound = bound.max(tokens),
+                Tokens::Omega => return None,
+            }
+        }
+        Some(bound)
+    }
+
+    pub fn unbounded_places(&self) -> Vec<PlaceRef> {
+        self.places
+            .iter()
+            .copied()
+            .filter(|place| self.bound(*place).is_none())
+            .collect()
+    }
+
+    /// `None` if the place is bounded
+    pub fn unbounded_witness(&self, place: PlaceRef) -> Option<UnboundedWitness> {
+        self.nodes.iter().enumerate().find_map(|(node, content)| {
+            content
+                .accelerated
+                .iter()
+                .find(|(index, _)| *index == place.index)
+                .map(|(_, ancestor)| UnboundedWitness {
+                    prefix: self.path(0, *ancestor),
+                    pump: self.path(*ancestor, node),
+                })
+        })
+    }
+
+    /// transitions on the tree path from `from` to its descendant `to`
+    fn path(&self, from: usize, to: usize) -> Vec<TransitionRef> {
+        let mut path = Vec::new();
+        let mut current = to;
+        while current != from {
+            let (parent, transition) = self.nodes[current]
+                .parent
+                .expect("path end is no descendant of path start");
+            path.push(transition);
+            current = parent;
+        }
+        path.reverse();
+        path
+    }
+
+    fn ancestors(&self, node: usize) -> impl Iterator<Item = usize> + '_ {
+        std::iter::successors(Some(node), move |node| {
+            self.nodes[*node].parent.map(|(parent, _)| parent)
+        })
+    }
+}
+
+impl PetriNet {
+    /// Fails with `UnsupportedFeature` for nets with inhibitor or reset arcs
+    /// or priorities, the construction does not terminate or is not sound
+    /// for them.
+    /// Capacities have to be replaced by `complement_capacities` first.
+    pub fn coverability_tree(&self) -> Result<CoverabilityTree> {
+        if self.uses_arc_kind(ArcKind::Inhibitor) || self.uses_arc_kind(ArcKind::Reset) {
+            return Err(PetriError::UnsupportedFeature(
+                "coverability trees of nets with inhibitor or reset arcs".into(),
+            ));
+        }
+        if self.has_priorities() {
+            return Err(PetriError::UnsupportedFeature(
+                "coverability trees of nets with priorities".into(),
+            ));
+        }
+        if self.has_capacities() {
+            return Err(PetriError::UnsupportedFeature(
+                "coverability trees of nets with capacities".into(),
+            ));
+        }
+        let initial = OmegaMarking {
+            tokens: self
+                .initial_marking()
+                .tokens
+                .into_iter()
+                .map(Tokens::Finite)
+                .collect(),
+        };
+        let mut tree = CoverabilityTree {
+            nodes: vec![CoverabilityNode {
+                marking: initial,
+                parent: None,
+                accelerated: Vec::new(),
+            }],
+            places: self.places().collect(),
+        };
+        let mut expanded = HashSet::new();
+        let mut pending = vec![0];
+        while let Some(node) = pending.pop() {
+            // nodes with a marking that was already expanded are leaves
+            if !expanded.insert(tree.nodes[node].marking.clone()) {
+                continue;
+            }
+            let marking = tree.nodes[node].marking.clone();
+            for transition in self.transitions() {
+                let (consume, produce) = self.weights(transition)?;
+                let read = self.special_weights(transition, ArcKind::Read)?;
+                if !consume
+                    .iter()
+                    .chain(read.iter())
+                    .all(|(place, weight)| marking.tokens(*place).covers(*weight))
+                {
+                    continue;
+                }
+                let mut successor = marking.clone();
+                for (place, weight) in consume {
+                    if let Tokens::Finite(tokens) = successor.tokens[place.index] {
+                        successor.tokens[place.index] = Tokens::Finite(tokens - weight);
+                    }
+                }
+                for (place, weight) in produce {
+                    if let Tokens::Finite(tokens) = successor.tokens[place.index] {
+                        successor.tokens[place.index] = Tokens::Finite(tokens + weight);
+                    }
+                }
+                // accelerate places that grow compared to a covered ancestor
+                let mut accelerated = Vec::new();
+                for ancestor in tree.ancestors(node) {
+                    let ancestor_marking = &tree.nodes[ancestor].marking;
+                    if successor != *ancestor_marking && successor.covers(ancestor_marking) {
+                        for index in 0..successor.tokens.len() {
+                            if successor.tokens[index] != ancestor_marking.tokens[index]
+                                && successor.tokens[index] != Tokens::Omega
+                            {
+                                successor.tokens[index] = Tokens::Omega;
+                                accelerated.push((index, ancestor));
+                            }
+                        }
+                    }
+                }
+                tree.nodes.push(CoverabilityNode {
+                    marking: successor,
+                    parent: Some((node, transition)),
+                    accelerated,
+                });
+                pending.push(tree.nodes.len() - 1);
+            }
+        }
+        Ok(tree)
+    }
+}
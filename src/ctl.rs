@@ -0,0 +1,389 @@
+use crate::{
+    ExplorationLimits, FiringSequence, Marking, PetriError, PetriNet, PlaceRef, ReachabilityGraph,
+    Result, TransitionRef,
+};
+use std::collections::hash_map::Entry;
+use std::collections::{HashMap, VecDeque};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Comparison {
+    Less,
+    LessOrEqual,
+    Equal,
+    NotEqual,
+    GreaterOrEqual,
+    Greater,
+}
+
+impl Comparison {
+    pub fn compare(self, left: usize, right: usize) -> bool {
+        match self {
+            Comparison::Less => left < right,
+            Comparison::LessOrEqual => left <= right,
+            Comparison::Equal => left == right,
+            Comparison::NotEqual => left != right,
+            Comparison::GreaterOrEqual => left >= right,
+            Comparison::Greater => left > right,
+        }
+    }
+}
+
+/// Proposition about a single marking.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Atom {
+    /// tokens on the place compared to a constant, e.g. `p_3 >= 2`
+    Tokens(PlaceRef, Comparison, usize),
+    /// the transition is enabled
+    Fireable(TransitionRef),
+    /// no transition is enabled
+    Deadlock,
+}
+
+impl Atom {
+    pub fn holds(&self, net: &PetriNet, marking: &Marking) -> Result<bool> {
+        match self {
+            Atom::Tokens(place, comparison, tokens) => {
+                net.place(*place)?;
+                Ok(comparison.compare(marking.tokens(*place), *tokens))
+            }
+            Atom::Fireable(transition) => transition.is_enabled(net, marking),
+            Atom::Deadlock => Ok(net.enabled_transitions(marking).next().is_none()),
+        }
+    }
+}
+
+/// Formula of computation tree logic. Paths are maximal, they end in dead
+/// markings or go on forever.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Ctl {
+    True,
+    False,
+    Atom(Atom),
+    Not(Box<Ctl>),
+    And(Vec<Ctl>),
+    Or(Vec<Ctl>),
+    /// some successor satisfies the formula
+    ExistsNext(Box<Ctl>),
+    /// all successors satisfy the formula, true in dead markings
+    AllNext(Box<Ctl>),
+    ExistsUntil(Box<Ctl>, Box<Ctl>),
+    AllUntil(Box<Ctl>, Box<Ctl>),
+    ExistsFinally(Box<Ctl>),
+    AllFinally(Box<Ctl>),
+    ExistsGlobally(Box<Ctl>),
+    AllGlobally(Box<Ctl>),
+}
+
+/// Infinite run that fires `prefix` once and then repeats `cycle` forever.
+/// An empty cycle means that the run stutters in the dead marking reached by
+/// `prefix`: runs that end in a deadlock repeat their last marking forever.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Lasso {
+    pub prefix: Vec<TransitionRef>,
+    pub cycle: Vec<TransitionRef>,
+}
+
+/// Run of the net that shows why a formula holds or does not hold.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Witness {
+    /// finite run, for paths that end in a dead marking it is maximal
+    Path(FiringSequence),
+    Lasso(Lasso),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Verdict {
+    /// the formula holds in the initial marking
+    pub holds: bool,
+    /// Run from the initial marking that proves the verdict. Only available
+    /// if the outermost operator (below negations) is a temporal operator
+    /// that is decided by a single run, like `EF` holding or `AG` failing.
+    pub witness: Option<Witness>,
+}
+
+impl PetriNet {
+    /// Evaluates the formula on the reachability graph, which is built with
+    /// the given limits. Fails with `LimitsExceeded` if the graph is not
+    /// complete.
+    pub fn check_ctl(&self, formula: &Ctl, limits: &ExplorationLimits) -> Result<Verdict> {
+        let graph = self.reachability_graph(limits)?;
+        if !graph.is_complete() {
+            return Err(PetriError::LimitsExceeded);
+        }
+        let checker = Checker::new(self, &graph);
+        let holds = checker.states(formula)?[0];
+        Ok(Verdict {
+            holds,
+            witness: checker.witness(formula, holds)?,
+        })
+    }
+}
+
+/// Labels the states of a reachability graph with the subformulas that hold.
+struct Checker<'a> {
+    net: &'a PetriNet,
+    graph: &'a ReachabilityGraph,
+    /// incoming edge indices per state
+    predecessors: Vec<Vec<usize>>,
+}
+
+impl<'a> Checker<'a> {
+    fn new(net: &'a PetriNet, graph: &'a ReachabilityGraph) -> Self {
+        let mut predecessors = vec![Vec::new(); graph.markings().len()];
+        for (index, edge) in graph.edges().iter().enumerate() {
+            predecessors[edge.target].push(index);
+        }
+        Checker {
+            net,
+            graph,
+            predecessors,
+        }
+    }
+
+    /// states that satisfy the formula
+    fn states(&self, formula: &Ctl) -> Result<Vec<bool>> {
+        let count = self.graph.markings().len();
+        let all = || vec![true; count];
+        Ok(match formula {
+            Ctl::True => all(),
+            Ctl::False => vec![false; count],
+            Ctl::Atom(atom) => self
+                .graph
+                .markings()
+                .iter()
+                .map(|marking| atom.holds(self.net, marking))
+                .collect::<Result<_>>()?,
+            Ctl::Not(formula) => negate(self.states(formula)?),
+            Ctl::And(formulas) => {
+                let mut states = all();
+                for formula in formulas {
+                    for (state, holds) in states.iter_mut().zip(self.states(formula)?) {
+                        *state &= holds;
+                    }
+                }
+                states
+            }
+            Ctl::Or(formulas) => {
+                let mut states = vec![false; count];
+                for formula in formulas {
+                    for (state, holds) in states.iter_mut().zip(self.states(formula)?) {
+                        *state |= holds;
+                    }
+                }
+                states
+            }
+            Ctl::ExistsNext(formula) => {
+                let next = self.states(formula)?;
+                (0..count)
+                    .map(|state| self.graph.successors(state).any(|edge| next[edge.target]))
+                    .collect()
+            }
+            Ctl::AllNext(formula) => {
+                let next = self.states(formula)?;
+                (0..count)
+                    .map(|state| self.graph.successors(state).all(|edge| next[edge.target]))
+                    .collect()
+            }
+            Ctl::ExistsUntil(hold, goal) => {
+                self.exists_until(&self.states(hold)?, self.states(goal)?)
+            }
+            Ctl::AllUntil(hold, goal) => self.all_until(&self.states(hold)?, self.states(goal)?),
+            Ctl::ExistsFinally(goal) => self.exists_until(&all(), self.states(goal)?),
+            Ctl::AllFinally(goal) => self.all_until(&all(), self.states(goal)?),
+            Ctl::ExistsGlobally(hold) => self.exists_globally(self.states(hold)?),
+            Ctl::AllGlobally(hold) => negate(self.exists_until(&all(), negate(self.states(hold)?))),
+        })
+    }
+
+    /// least fixpoint of `goal ∨ (hold ∧ EX states)`
+    fn exists_until(&self, hold: &[bool], goal: Vec<bool>) -> Vec<bool> {
+        let mut states = goal;
+        let mut pending: Vec<usize> = (0..states.len()).filter(|state| states[*state]).collect();
+        while let Some(state) = pending.pop() {
+            for edge in &self.predecessors[state] {
+                let source = self.graph.edges()[*edge].source;
+                if !states[source] && hold[source] {
+                    states[source] = true;
+                    pending.push(source);
+                }
+            }
+        }
+        states
+    }
+
+    /// Least fixpoint of `goal ∨ (hold ∧ AX states ∧ EX true)`, dead
+    /// markings only satisfy it through `goal`.
+    fn all_until(&self, hold: &[bool], goal: Vec<bool>) -> Vec<bool> {
+        let mut states = goal;
+        // successor edges that do not lead into the fixpoint yet
+        let mut remaining: Vec<usize> = (0..states.len())
+            .map(|state| self.graph.successors(state).count())
+            .collect();
+        let mut pending: Vec<usize> = (0..states.len()).filter(|state| states[*state]).collect();
+        while let Some(state) = pending.pop() {
+            for edge in &self.predecessors[state] {
+                let source = self.graph.edges()[*edge].source;
+                if states[source] || !hold[source] {
+                    continue;
+                }
+                remaining[source] -= 1;
+                if remaining[source] == 0 {
+                    states[source] = true;
+                    pending.push(source);
+                }
+            }
+        }
+        states
+    }
+
+    /// greatest fixpoint of `hold ∧ (EX states ∨ dead)`
+    fn exists_globally(&self, hold: Vec<bool>) -> Vec<bool> {
+        let mut states = hold;
+        let successors: Vec<usize> = (0..states.len())
+            .map(|state| self.graph.successors(state).count())
+            .collect();
+        // successor edges that lead into the fixpoint
+        let mut inside: Vec<usize> = (0..states.len())
+            .map(|state| {
+                self.graph
+                    .successors(state)
+                    .filter(|edge| states[edge.target])
+                    .count()
+            })
+            .collect();
+        let mut pending = Vec::new();
+        for state in 0..states.len() {
+            if states[state] && successors[state] > 0 && inside[state] == 0 {
+                states[state] = false;
+                pending.push(state);
+            }
+        }
+        while let Some(state) = pending.pop() {
+            for edge in &self.predecessors[state] {
+                let source = self.graph.edges()[*edge].source;
+                if !states[source] {
+                    continue;
+                }
+                inside[source] -= 1;
+                if inside[source] == 0 {
+                    states[source] = false;
+                    pending.push(source);
+                }
+            }
+        }
+        states
+    }
+
+    fn witness(&self, formula: &Ctl, holds: bool) -> Result<Option<Witness>> {
+        let all = vec![true; self.graph.markings().len()];
+        Ok(match (formula, holds) {
+            (Ctl::Not(formula), _) => self.witness(formula, !holds)?,
+            (Ctl::ExistsNext(formula), true) => self.step(&self.states(formula)?),
+            (Ctl::AllNext(formula), false) => self.step(&negate(self.states(formula)?)),
+            (Ctl::ExistsFinally(goal), true) => self.path(&all, &self.states(goal)?),
+            (Ctl::AllGlobally(hold), false) => self.path(&all, &negate(self.states(hold)?)),
+            (Ctl::ExistsUntil(hold, goal), true) => {
+                self.path(&self.states(hold)?, &self.states(goal)?)
+            }
+            (Ctl::ExistsGlobally(hold), true) => {
+                self.lasso(&self.exists_globally(self.states(hold)?))
+            }
+            (Ctl::AllFinally(goal), false) => {
+                self.lasso(&self.exists_globally(negate(self.states(goal)?)))
+            }
+            (Ctl::AllUntil(hold, goal), false) => {
+                // the goal is missed by leaving hold first or by avoiding it forever
+                let hold = self.states(hold)?;
+                let missed = negate(self.states(goal)?);
+                let violation: Vec<bool> = hold
+                    .iter()
+                    .zip(&missed)
+                    .map(|(hold, missed)| !hold && *missed)
+                    .collect();
+                match self.path(&missed, &violation) {
+                    Some(path) => Some(path),
+                    None => self.lasso(&self.exists_globally(missed)),
+                }
+            }
+            _ => None,
+        })
+    }
+
+    /// single transition from the initial marking into `target`
+    fn step(&self, target: &[bool]) -> Option<Witness> {
+        let edge = self.graph.successors(0).find(|edge| target[edge.target])?;
+        Some(self.sequence(vec![edge.transition], edge.target))
+    }
+
+    /// shortest run to a `target` state that only passes `allowed` states
+    fn path(&self, allowed: &[bool], target: &[bool]) -> Option<Witness> {
+        let mut parents: HashMap<usize, Option<(usize, TransitionRef)>> = HashMap::new();
+        parents.insert(0, None);
+        let mut pending = VecDeque::new();
+        pending.push_back(0);
+        while let Some(state) = pending.pop_front() {
+            if target[state] {
+                let mut transitions = Vec::new();
+                let mut current = state;
+                while let Some((parent, transition)) = parents[&current] {
+                    transitions.push(transition);
+                    current = parent;
+                }
+                transitions.reverse();
+                return Some(self.sequence(transitions, state));
+            }
+            if !allowed[state] {
+                continue;
+            }
+            for edge in self.graph.successors(state) {
+                if let Entry::Vacant(entry) = parents.entry(edge.target) {
+                    entry.insert(Some((state, edge.transition)));
+                    pending.push_back(edge.target);
+                }
+            }
+        }
+        None
+    }
+
+    /// Maximal run that stays in `states`, which has to be closed under
+    /// `EG`. It either ends in a dead marking or repeats a cycle.
+    fn lasso(&self, states: &[bool]) -> Option<Witness> {
+        if !states[0] {
+            return None;
+        }
+        let mut visited = HashMap::new();
+        let mut transitions = Vec::new();
+        let mut state = 0;
+        loop {
+            visited.insert(state, transitions.len());
+            let edge = match self
+                .graph
+                .successors(state)
+                .find(|edge| states[edge.target])
+            {
+                Some(edge) => edge,
+                None => return Some(self.sequence(transitions, state)),
+            };
+            transitions.push(edge.transition);
+            if let Some(position) = visited.get(&edge.target) {
+                let cycle = transitions.split_off(*position);
+                return Some(Witness::Lasso(Lasso {
+                    prefix: transitions,
+                    cycle,
+                }));
+            }
+            state = edge.target;
+        }
+    }
+
+    fn sequence(&self, transitions: Vec<TransitionRef>, state: usize) -> Witness {
+        Witness::Path(FiringSequence {
+            transitions,
+            marking: self.graph.markings()[state].clone(),
+        })
+    }
+}
+
+fn negate(states: Vec<bool>) -> Vec<bool> {
+    states.into_iter().map(|holds| !holds).collect()
+}
@@ -0,0 +1,364 @@
+use crate::matrix::write_matrix_market;
+use crate::{
+    ExplorationLimits, Marking, PetriError, PetriNet, PlaceRef, Result, SearchOrder,
+    StochasticKind, TransitionRef,
+};
+use std::collections::{BTreeMap, HashMap, HashSet, VecDeque};
+
+/// Continuous-time Markov chain of a generalized stochastic Petri net.
+/// States are the tangible markings, markings that enable immediate
+/// transitions are left in zero time and are eliminated.
+#[derive(Debug, Clone)]
+pub struct Ctmc {
+    markings: Vec<Marking>,
+    /// probability to start in a state, the initial marking can be vanishing
+    initial: Vec<f64>,
+    /// transition rates between different states
+    rates: BTreeMap<(usize, usize), f64>,
+    /// enabled exponential transitions with their rates per state
+    exits: Vec<Vec<(TransitionRef, f64)>>,
+    immediate: HashSet<TransitionRef>,
+}
+
+impl Ctmc {
+    pub fn markings(&self) -> &[Marking] {
+        &self.markings
+    }
+
+    pub fn initial_distribution(&self) -> &[f64] {
+        &self.initial
+    }
+
+    /// Infinitesimal generator as (row, column, rate) triples in row major
+    /// order. Diagonal entries hold the negated exit rate of a state and are
+    /// left out for states without exits.
+    pub fn generator(&self) -> Vec<(usize, usize, f64)> {
+        let mut diagonal = vec![0.0; self.markings.len()];
+        for ((source, _), rate) in &self.rates {
+            diagonal[*source] -= rate;
+        }
+        let mut entries: Vec<(usize, usize, f64)> = self
+            .rates
+            .iter()
+            .map(|((source, target), rate)| (*source, *target, *rate))
+            .chain(
+                diagonal
+                    .iter()
+                    .enumerate()
+                    .filter(|(_, rate)| **rate != 0.0)
+                    .map(|(state, rate)| (state, state, *rate)),
+            )
+            .collect();
+        entries.sort_by_key(|(row, column, _)| (*row, *column));
+        entries
+    }
+
+    pub fn to_matrix_market_string(&self) -> std::result::Result<String, std::io::Error> {
+        let mut writer = Vec::new();
+        self.to_matrix_market(&mut writer)?;
+        Ok(String::from_utf8(writer).expect("Document generated non UTF-8 string"))
+    }
+
+    /// generator matrix in MatrixMarket coordinate format, row and column `i`
+    /// belong to the `i`-th marking (counted from one)
+    pub fn to_matrix_market<T>(&self, writer: &mut T) -> std::result::Result<(), std::io::Error>
+    where
+        T: std::io::Write,
+    {
+        let states = self.markings.len();
+        write_matrix_market(writer, "real", states, states, &self.generator())
+    }
+
+    /// Long run probability of every state, computed with the power method on
+    /// the uniformized chain starting from the initial distribution.
+    /// Fails with `InvalidData` if the largest change of a probability is
+    /// still above `tolerance` after `max_iterations`.
+    pub fn steady_state(&self, tolerance: f64, max_iterations: usize) -> Result<Vec<f64>> {
+        let mut exit_rates = vec![0.0; self.markings.len()];
+        for ((source, _), rate) in &self.rates {
+            exit_rates[*source] += rate;
+        }
+        // a uniformization rate above every exit rate keeps the chain aperiodic
+        let uniformization = exit_rates.iter().cloned().fold(0.0, f64::max) * 1.1;
+        if uniformization == 0.0 {
+            return Ok(self.initial.clone());
+        }
+        let mut distribution = self.initial.clone();
+        for _ in 0..max_iterations {
+            let mut next: Vec<f64> = distribution
+                .iter()
+                .zip(&exit_rates)
+                .map(|(probability, exit)| probability * (1.0 - exit / uniformization))
+                .collect();
+            for ((source, target), rate) in &self.rates {
+                next[*target] += distribution[*source] * rate / uniformization;
+            }
+            let change = next
+                .iter()
+                .zip(&distribution)
+                .map(|(a, b)| (a - b).abs())
+                .fold(0.0, f64::max);
+            distribution = next;
+            if change <= tolerance {
+                return Ok(distribution);
+            }
+        }
+        Err(PetriError::InvalidData(format!(
+            "steady state did not converge within {} iterations",
+            max_iterations
+        )))
+    }
+
+    /// expected number of tokens on the place under the given distribution
+    pub fn mean_tokens(&self, distribution: &[f64], place: PlaceRef) -> f64 {
+        self.markings
+            .iter()
+            .zip(distribution)
+            .map(|(marking, probability)| marking.tokens(place) as f64 * probability)
+            .sum()
+    }
+
+    /// Expected firings per time unit of an exponential transition under the
+    /// given distribution. Immediate transitions fire in eliminated markings,
+    /// their throughput is not available and fails with `InvalidData`.
+    pub fn throughput(&self, distribution: &[f64], transition: TransitionRef) -> Result<f64> {
+        if self.immediate.contains(&transition) {
+            return Err(PetriError::InvalidData(format!(
+                "the throughput of immediate transition t_{} is not part of the chain",
+                transition.index
+            )));
+        }
+        let mut throughput = 0.0;
+        for (exits, probability) in self.exits.iter().zip(distribution) {
+            for (exit, rate) in exits {
+                if *exit == transition {
+                    throughput += rate * probability;
+                }
+            }
+        }
+        Ok(throughput)
+    }
+}
+
+impl PetriNet {
+    /// Markov chain of a bounded net where every transition has a stochastic
+    /// firing behaviour. Only the markings reachable when enabled immediate
+    /// transitions pre-empt exponential ones are explored, within the given
+    /// limits, it fails with `LimitsExceeded` if they are not all explored.
+    /// Fails with `UnsupportedFeature` if immediate transitions can fire in a
+    /// loop forever without reaching a tangible marking, and with
+    /// `InvalidData` if the probabilities to leave the vanishing markings
+    /// do not converge.
+    pub fn ctmc(&self, limits: &ExplorationLimits) -> Result<Ctmc> {
+        let kinds: HashMap<TransitionRef, StochasticKind> =
+            self.stochastic_kinds()?.into_iter().collect();
+        // immediate transitions take priority, only the markings reached
+        // under that priority are explored, with weights and rates per state
+        let mut markings = vec![self.initial_marking()];
+        let mut states: HashMap<Marking, usize> = HashMap::new();
+        states.insert(markings[0].clone(), 0);
+        let mut moves: Vec<Vec<(usize, TransitionRef, f64)>> = vec![Vec::new()];
+        let mut vanishing = vec![false];
+        // markings are stored in the chain and the lookup table
+        let marking_size =
+            2 * (std::mem::size_of::<Marking>() + self.places.len() * std::mem::size_of::<usize>());
+        let move_size = std::mem::size_of::<(usize, TransitionRef, f64)>();
+        let mut memory = marking_size;
+        let mut pending = VecDeque::new();
+        pending.push_back(0);
+        while let Some(state) = match limits.order {
+            SearchOrder::BreadthFirst => pending.pop_front(),
+            SearchOrder::DepthFirst => pending.pop_back(),
+        } {
+            let marking = markings[state].clone();
+            let enabled: Vec<_> = self
+                .enabled_transitions(&marking)
+                .map(|transition| (transition, kinds[&transition]))
+                .collect();
+            let immediate: Vec<(TransitionRef, f64)> = enabled
+                .iter()
+                .filter_map(|(transition, kind)| match kind {
+                    StochasticKind::Immediate { weight } => Some((*transition, *weight)),
+                    StochasticKind::Exponential { .. } => None,
+                })
+                .collect();
+            let choices = if immediate.is_empty() {
+                enabled
+                    .iter()
+                    .filter_map(|(transition, kind)| match kind {
+                        StochasticKind::Exponential { rate } => Some((*transition, *rate)),
+                        StochasticKind::Immediate { .. } => None,
+                    })
+                    .collect()
+            } else {
+                vanishing[state] = true;
+                let total: f64 = immediate.iter().map(|(_, weight)| weight).sum();
+                immediate
+                    .into_iter()
+                    .map(|(transition, weight)| (transition, weight / total))
+                    .collect::<Vec<_>>()
+            };
+            for (transition, value) in choices {
+                let mut successor = marking.clone();
+                transition.fire(self, &mut successor)?;
+                let target = match states.get(&successor) {
+                    Some(target) => *target,
+                    None => {
+                        let exceeds_states =
+                            limits.max_states.is_some_and(|max| markings.len() >= max);
+                        let exceeds_memory = limits
+                            .max_memory
+                            .is_some_and(|max| memory + marking_size + move_size > max);
+                        if exceeds_states || exceeds_memory {
+                            return Err(PetriError::LimitsExceeded);
+                        }
+                        memory += marking_size;
+                        let target = markings.len();
+                        states.insert(successor.clone(), target);
+                        markings.push(successor);
+                        moves.push(Vec::new());
+                        vanishing.push(false);
+                        pending.push_back(target);
+                        target
+                    }
+                };
+                if limits
+                    .max_memory
+                    .is_some_and(|max| memory + move_size > max)
+                {
+                    return Err(PetriError::LimitsExceeded);
+                }
+                memory += move_size;
+                moves[state].push((target, transition, value));
+            }
+        }
+        let absorption = absorption(&moves, &vanishing)?;
+        let tangible: Vec<usize> = (0..moves.len())
+            .filter(|state| !vanishing[*state])
+            .collect();
+        let index: HashMap<usize, usize> = tangible
+            .iter()
+            .enumerate()
+            .map(|(index, state)| (*state, index))
+            .collect();
+        // probability to end up in a tangible state, tangible states reach themselves
+        let resolve = |state: usize| -> Vec<(usize, f64)> {
+            if vanishing[state] {
+                absorption[&state]
+                    .iter()
+                    .map(|(target, probability)| (index[target], *probability))
+                    .collect()
+            } else {
+                vec![(index[&state], 1.0)]
+            }
+        };
+        let mut ctmc = Ctmc {
+            markings: tangible
+                .iter()
+                .map(|state| markings[*state].clone())
+                .collect(),
+            initial: vec![0.0; tangible.len()],
+            rates: BTreeMap::new(),
+            exits: Vec::new(),
+            immediate: kinds
+                .iter()
+                .filter(|(_, kind)| matches!(kind, StochasticKind::Immediate { .. }))
+                .map(|(transition, _)| *transition)
+                .collect(),
+        };
+        for (target, probability) in resolve(0) {
+            ctmc.initial[target] += probability;
+        }
+        for (source, state) in tangible.iter().enumerate() {
+            let mut exits = Vec::new();
+            for (target, transition, rate) in &moves[*state] {
+                exits.push((*transition, *rate));
+                for (target, probability) in resolve(*target) {
+                    if target != source {
+                        *ctmc.rates.entry((source, target)).or_insert(0.0) += rate * probability;
+                    }
+                }
+            }
+            ctmc.exits.push(exits);
+        }
+        Ok(ctmc)
+    }
+}
+
+/// Gauss-Seidel sweeps after which the absorption probabilities are
+/// considered not to converge
+const ABSORPTION_ITERATIONS: usize = 10_000;
+/// largest change of an absorption probability in a converged sweep
+const ABSORPTION_TOLERANCE: f64 = 1e-12;
+
+/// Probabilities to reach each tangible state from a vanishing state,
+/// solved with Gauss-Seidel iterations on `x = r + q x`.
+fn absorption(
+    moves: &[Vec<(usize, TransitionRef, f64)>],
+    vanishing: &[bool],
+) -> Result<HashMap<usize, HashMap<usize, f64>>> {
+    let states: Vec<usize> = (0..moves.len()).filter(|state| vanishing[*state]).collect();
+    // every vanishing state has to lead to a tangible one, otherwise the
+    // iteration would converge to probabilities that do not sum up to one
+    let mut predecessors: HashMap<usize, Vec<usize>> = HashMap::new();
+    for state in &states {
+        for (target, _, _) in &moves[*state] {
+            predecessors.entry(*target).or_default().push(*state);
+        }
+    }
+    let mut leaving: HashSet<usize> = HashSet::new();
+    let mut pending: Vec<usize> = (0..moves.len())
+        .filter(|state| !vanishing[*state])
+        .collect();
+    while let Some(state) = pending.pop() {
+        for predecessor in predecessors.get(&state).into_iter().flatten() {
+            if leaving.insert(*predecessor) {
+                pending.push(*predecessor);
+            }
+        }
+    }
+    if states.iter().any(|state| !leaving.contains(state)) {
+        return Err(PetriError::UnsupportedFeature(
+            "immediate transitions that fire forever without reaching a tangible marking".into(),
+        ));
+    }
+    let mut absorption: HashMap<usize, HashMap<usize, f64>> = states
+        .iter()
+        .map(|state| (*state, HashMap::new()))
+        .collect();
+    for _ in 0..ABSORPTION_ITERATIONS {
+        let mut change: f64 = 0.0;
+        for state in &states {
+            let mut next: HashMap<usize, f64> = HashMap::new();
+            for (target, _, probability) in &moves[*state] {
+                if vanishing[*target] {
+                    for (tangible, p) in &absorption[target] {
+                        *next.entry(*tangible).or_insert(0.0) += probability * p;
+                    }
+                } else {
+                    *next.entry(*target).or_insert(0.0) += probability;
+                }
+            }
+            for (tangible, p) in &next {
+                let old = absorption[state].get(tangible).copied().unwrap_or(0.0);
+                change = change.max((p - old).abs());
+            }
+            absorption.insert(*state, next);
+        }
+        if change < ABSORPTION_TOLERANCE {
+            // every vanishing state is left for sure, the rest of the
+            // iteration error is spread over the targets
+            for targets in absorption.values_mut() {
+                let total: f64 = targets.values().sum();
+                for probability in targets.values_mut() {
+                    *probability /= total;
+                }
+            }
+            return Ok(absorption);
+        }
+    }
+    Err(PetriError::InvalidData(format!(
+        "absorption probabilities of the vanishing markings did not converge within {} iterations",
+        ABSORPTION_ITERATIONS
+    )))
+}
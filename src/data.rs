@@ -1,19 +1,31 @@
-use crate::{NodeRef, PlaceRef, TransitionRef};
-use std::collections::HashMap;
+use crate::{ArcRef, NodeRef, PlaceRef, StochasticKind, TimeInterval, TransitionRef};
+use std::collections::BTreeMap;
 
 #[derive(Debug, Clone)]
 pub struct Place {
     pub name: Option<String>,
     pub marking: usize,
-    pub preset: HashMap<TransitionRef, usize>,
-    pub postset: HashMap<TransitionRef, usize>,
+    /// maximum amount of tokens, `None` for unbounded places
+    pub capacity: Option<usize>,
+    /// arcs by the transition they connect to
+    pub preset: BTreeMap<TransitionRef, ArcRef>,
+    pub postset: BTreeMap<TransitionRef, ArcRef>,
+    /// arcs that do not move tokens, by the transition they lead to
+    pub special: BTreeMap<(TransitionRef, ArcKind), ArcRef>,
 }
 
 #[derive(Debug, Clone)]
 pub struct Transition {
     pub name: Option<String>,
-    pub preset: HashMap<PlaceRef, usize>,
-    pub postset: HashMap<PlaceRef, usize>,
+    /// static firing interval in time Petri nets
+    pub interval: Option<TimeInterval>,
+    /// firing rate or weight in stochastic Petri nets
+    pub stochastic: Option<StochasticKind>,
+    /// arcs by the place they connect to
+    pub preset: BTreeMap<PlaceRef, ArcRef>,
+    pub postset: BTreeMap<PlaceRef, ArcRef>,
+    /// arcs that do not move tokens, by the place they come from
+    pub special: BTreeMap<(PlaceRef, ArcKind), ArcRef>,
 }
 
 #[derive(Debug, Clone)]
@@ -21,6 +33,34 @@ pub struct Arc {
     pub name: Option<String>,
     pub source: NodeRef,
     pub sink: NodeRef,
-    /// multiplicity: amount of tokens that get consumed/produced
+    /// multiplicity: amount of tokens that get consumed/produced,
+    /// the threshold for inhibitor and read arcs
     pub mult: usize,
+    pub kind: ArcKind,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum ArcKind {
+    /// consumes or produces `mult` tokens
+    Normal,
+    /// leads from a place to a transition, the transition is only enabled
+    /// while the place holds less than `mult` tokens
+    Inhibitor,
+    /// leads from a place to a transition, the transition is only enabled
+    /// while the place holds at least `mult` tokens, they are not consumed
+    Read,
+    /// leads from a place to a transition, firing the transition removes all
+    /// tokens from the place
+    Reset,
+}
+
+impl ArcKind {
+    /// weight of two parallel arcs of this kind that are merged into one
+    pub(crate) fn merge(self, a: usize, b: usize) -> usize {
+        match self {
+            ArcKind::Normal => a + b,
+            ArcKind::Inhibitor => a.min(b),
+            ArcKind::Read | ArcKind::Reset => a.max(b),
+        }
+    }
 }
@@ -0,0 +1,95 @@
+use crate::{ExplorationLimits, Marking, PetriNet, Result, SearchOrder, TransitionRef};
+
+/// Transitions that fire one after another, starting in the initial marking.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FiringSequence {
+    pub(crate) transitions: Vec<TransitionRef>,
+    pub(crate) marking: Marking,
+}
+
+impl FiringSequence {
+    pub fn transitions(&self) -> &[TransitionRef] {
+        &self.transitions
+    }
+
+    /// marking after the last transition fired
+    pub fn marking(&self) -> &Marking {
+        &self.marking
+    }
+
+    /// The transitions separated by spaces, named transitions are printed
+    /// with their name, all others as `t_<index>`.
+    pub fn display<'a>(&'a self, net: &'a PetriNet) -> impl std::fmt::Display + 'a {
+        DisplaySequence {
+            sequence: self,
+            net,
+        }
+    }
+}
+
+struct DisplaySequence<'a> {
+    sequence: &'a FiringSequence,
+    net: &'a PetriNet,
+}
+
+impl std::fmt::Display for DisplaySequence<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for (position, transition) in self.sequence.transitions.iter().enumerate() {
+            if position > 0 {
+                write!(f, " ")?;
+            }
+            match self
+                .net
+                .transition(*transition)
+                .ok()
+                .and_then(|data| data.name.as_ref())
+            {
+                Some(name) => write!(f, "{}", name)?,
+                None => write!(f, "t_{}", transition.index)?,
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Outcome of a deadlock search.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DeadlockSearch {
+    /// shortest firing sequence to a marking in which no transition is enabled
+    Found(FiringSequence),
+    /// every reachable marking enables a transition
+    DeadlockFree,
+    /// the limits were reached before a dead marking was found, freedom of
+    /// deadlocks is unknown
+    NotFoundWithinLimits,
+}
+
+impl PetriNet {
+    /// Explores the reachable markings breadth first and looks for a marking
+    /// in which no transition is enabled. The search order of the limits is
+    /// ignored.
+    ///
+    /// The search takes exploration limits instead of no arguments, so it
+    /// also ends on nets with infinitely many reachable markings. Hitting the
+    /// limits is an outcome of the search and not an error.
+    pub fn find_deadlock(&self, limits: &ExplorationLimits) -> Result<DeadlockSearch> {
+        let limits = ExplorationLimits {
+            order: SearchOrder::BreadthFirst,
+            ..*limits
+        };
+        let graph = self.reachability_graph(&limits)?;
+        // breadth first states are numbered by their distance to the initial marking
+        let dead = (0..graph.markings().len())
+            .find(|state| graph.is_explored(*state) && graph.successors(*state).next().is_none());
+        Ok(match dead {
+            Some(state) => DeadlockSearch::Found(FiringSequence {
+                transitions: graph
+                    .path_to(state)
+                    .expect("the state is part of the graph"),
+                marking: graph.markings()[state].clone(),
+            }),
+            None if graph.is_complete() => DeadlockSearch::DeadlockFree,
+            None => DeadlockSearch::NotFoundWithinLimits,
+        })
+    }
+}
@@ -1,8 +1,9 @@
-use crate::{PetriNet, PlaceRef, TransitionRef};
-use std::convert::TryFrom;
+use crate::names::NodeIds;
+use crate::{ArcKind, Marking, PetriNet, ReachabilityGraph, TransitionRef};
 
 const PLACE_PREFIX: &str = "p_";
 const TRANSITION_PREFIX: &str = "t_";
+const MARKING_PREFIX: &str = "m_";
 
 impl PetriNet {
     pub fn to_dot_string(&self) -> Result<String, std::io::Error> {
@@ -26,27 +27,28 @@ impl PetriNet {
     where
         T: std::io::Write,
     {
-        writer.write("digraph petrinet {\n".as_bytes())?;
-        self.write_dot_places(writer)?;
-        self.write_dot_transitions(writer)?;
-        self.write_dot_arcs(writer)?;
-        writer.write("}".as_bytes())?;
+        let ids = self.node_ids(PLACE_PREFIX, TRANSITION_PREFIX);
+        writer.write_all("digraph petrinet {\n".as_bytes())?;
+        self.write_dot_places(writer, &ids)?;
+        self.write_dot_transitions(writer, &ids)?;
+        self.write_dot_arcs(writer, &ids)?;
+        writer.write_all("}".as_bytes())?;
         Ok(())
     }
 
-    fn write_dot_places<T>(&self, writer: &mut T) -> Result<(), std::io::Error>
+    fn write_dot_places<T>(&self, writer: &mut T, ids: &NodeIds) -> Result<(), std::io::Error>
     where
         T: std::io::Write,
     {
         if !self.places.is_empty() {
-            for p in 0..self.places.len() {
-                let marking = self.places.get(p).unwrap().marking;
+            for place in self.places() {
+                let marking = self.place(place).unwrap().marking;
                 let marking = if marking > 0 {
                     let mut ret;
                     if marking < 5 {
                         ret = String::new();
                         for _ in 0..marking {
-                            ret.push_str("•");
+                            ret.push('•');
                         }
                     } else {
                         ret = marking.to_string();
@@ -56,106 +58,164 @@ impl PetriNet {
                     None
                 };
                 let line = format_dot_node(
-                    PLACE_PREFIX,
-                    p,
+                    ids.place(place),
                     "circle",
                     &marking,
-                    &self.places.get(p).unwrap().name,
+                    &self.place(place).unwrap().name,
                 );
-                writer.write(line.as_bytes())?;
+                writer.write_all(line.as_bytes())?;
             }
         }
         Ok(())
     }
 
-    fn write_dot_transitions<T>(&self, writer: &mut T) -> Result<(), std::io::Error>
+    fn write_dot_transitions<T>(&self, writer: &mut T, ids: &NodeIds) -> Result<(), std::io::Error>
     where
         T: std::io::Write,
     {
         if !self.transitions.is_empty() {
-            for t in 0..self.transitions.len() {
+            for transition in self.transitions() {
                 let line = format_dot_node(
-                    TRANSITION_PREFIX,
-                    t,
+                    ids.transition(transition),
                     "box",
-                    &self.transitions.get(t).unwrap().name,
+                    &self.transition(transition).unwrap().name,
                     &None,
                 );
-                writer.write(line.as_bytes())?;
+                writer.write_all(line.as_bytes())?;
             }
         }
         Ok(())
     }
 
-    fn write_dot_arcs<T>(&self, writer: &mut T) -> Result<(), std::io::Error>
+    fn write_dot_arcs<T>(&self, writer: &mut T, ids: &NodeIds) -> Result<(), std::io::Error>
     where
         T: std::io::Write,
     {
         let (tp, pt) = self.arcs_partitioned();
-        for (t, p, mult) in tp {
-            if mult > 0 {
-                let t = TransitionRef::try_from(t).unwrap();
-                let p = PlaceRef::try_from(p).unwrap();
-                let line = if mult > 1 {
-                    format!(
-                        "    {}{} -> {}{} [label=\"{}\"];\n",
-                        TRANSITION_PREFIX, t.index, PLACE_PREFIX, p.index, mult
-                    )
-                } else {
-                    format!(
-                        "    {}{} -> {}{};\n",
-                        TRANSITION_PREFIX, t.index, PLACE_PREFIX, p.index
-                    )
-                };
-                writer.write(line.as_bytes())?;
-            }
+        for (source, sink, mult) in tp.into_iter().chain(pt) {
+            let line = format_dot_arc(ids.node(source), ids.node(sink), mult, None);
+            writer.write_all(line.as_bytes())?;
         }
-        for (p, t, mult) in pt {
-            if mult > 0 {
-                let t = TransitionRef::try_from(t).unwrap();
-                let p = PlaceRef::try_from(p).unwrap();
-                let line = if mult > 1 {
-                    format!(
-                        "    {}{} -> {}{} [label=\"{}\"];\n",
-                        PLACE_PREFIX, p.index, TRANSITION_PREFIX, t.index, mult
-                    )
-                } else {
-                    format!(
-                        "    {}{} -> {}{};\n",
-                        PLACE_PREFIX, p.index, TRANSITION_PREFIX, t.index
-                    )
-                };
-                writer.write(line.as_bytes())?;
-            }
+        for arc in self.arcs() {
+            let arc = self.arc(arc).unwrap();
+            let style = match arc.kind {
+                ArcKind::Normal => continue,
+                ArcKind::Inhibitor => "arrowhead=\"odot\"",
+                ArcKind::Read => "style=\"dashed\" arrowhead=\"none\"",
+                ArcKind::Reset => "arrowhead=\"normalnormal\"",
+            };
+            // reset arcs empty the place, they have no weight to show
+            let mult = if arc.kind == ArcKind::Reset {
+                1
+            } else {
+                arc.mult
+            };
+            let line = format_dot_arc(ids.node(arc.source), ids.node(arc.sink), mult, Some(style));
+            writer.write_all(line.as_bytes())?;
+        }
+        Ok(())
+    }
+}
+
+impl ReachabilityGraph {
+    /// Markings are labeled with their marked places, edges with the fired
+    /// transitions. Node names of the net are used if they are set.
+    pub fn to_dot_string(&self, net: &PetriNet) -> Result<String, std::io::Error> {
+        let mut writer = Vec::new();
+        self.to_dot(net, &mut writer)?;
+        Ok(String::from_utf8(writer).expect("Document generated non UTF-8 string"))
+    }
+
+    pub fn to_dot<T>(&self, net: &PetriNet, writer: &mut T) -> Result<(), std::io::Error>
+    where
+        T: std::io::Write,
+    {
+        writer.write_all("digraph reachability {\n".as_bytes())?;
+        for (state, marking) in self.markings().iter().enumerate() {
+            let line = format_dot_node(
+                &format!("{}{}", MARKING_PREFIX, state),
+                "ellipse",
+                &Some(marking_label(net, marking)),
+                &None,
+            );
+            writer.write_all(line.as_bytes())?;
+        }
+        for edge in self.edges() {
+            let line = format!(
+                "    {}{} -> {}{} [label=\"{}\"];\n",
+                MARKING_PREFIX,
+                edge.source,
+                MARKING_PREFIX,
+                edge.target,
+                escape(&transition_label(net, edge.transition))
+            );
+            writer.write_all(line.as_bytes())?;
         }
+        writer.write_all("}".as_bytes())?;
         Ok(())
     }
 }
+
+fn transition_label(net: &PetriNet, transition: TransitionRef) -> String {
+    match net
+        .transition(transition)
+        .ok()
+        .and_then(|t| t.name.as_ref())
+    {
+        Some(name) => name.clone(),
+        None => format!("{}{}", TRANSITION_PREFIX, transition.index),
+    }
+}
+
+/// `p_0: 2, p_3: 1` with node names instead of ids if available
+fn marking_label(net: &PetriNet, marking: &Marking) -> String {
+    marking
+        .marked_places()
+        .map(|(place, tokens)| {
+            let name = match net.place(place).ok().and_then(|p| p.name.as_ref()) {
+                Some(name) => name.clone(),
+                None => format!("{}{}", PLACE_PREFIX, place.index),
+            };
+            format!("{}: {}", name, tokens)
+        })
+        .collect::<Vec<String>>()
+        .join(", ")
+}
+
+fn escape(label: &str) -> String {
+    label.replace("\n", "").replace("\"", "\\\"")
+}
+
 fn format_dot_node(
-    prefix: &str,
-    index: usize,
+    id: &str,
     shape: &str,
     label: &Option<String>,
     caption: &Option<String>,
 ) -> String {
     let label = if let Some(label) = label {
-        format!(
-            "label=\"{}\" ",
-            label.replace("\n", "").replace("\"", "\\\"")
-        )
+        format!("label=\"{}\" ", escape(label))
     } else {
         String::new()
     };
     let caption = if let Some(caption) = caption {
-        format!(
-            "xlabel=\"{}\" ",
-            caption.replace("\n", "").replace("\"", "\\\"")
-        )
+        format!("xlabel=\"{}\" ", escape(caption))
     } else {
         String::new()
     };
-    format!(
-        "    {}{} [shape=\"{}\" {} {}];\n",
-        prefix, index, shape, label, caption
-    )
+    format!("    {} [shape=\"{}\" {} {}];\n", id, shape, label, caption)
+}
+
+/// multiplicities above one become the label of the arc
+fn format_dot_arc(source: &str, sink: &str, mult: usize, style: Option<&str>) -> String {
+    let label = if mult > 1 {
+        Some(format!("label=\"{}\"", mult))
+    } else {
+        None
+    };
+    let attributes: Vec<String> = style.map(String::from).into_iter().chain(label).collect();
+    if attributes.is_empty() {
+        format!("    {} -> {};\n", source, sink)
+    } else {
+        format!("    {} -> {} [{}];\n", source, sink, attributes.join(" "))
+    }
 }
@@ -5,27 +5,56 @@ pub enum PetriError {
     PlaceNotFound,
     TransitionNotFound,
     ArcNotFound,
+    TransitionNotEnabled,
+    DuplicateName(String),
     PageNotFound,
     NetNotFound,
     ObjectNotFound,
     InvalidData(String),
     CorruptedData(String),
     XmlWriterError(xml::writer::Error),
+    XmlReaderError(xml::reader::Error),
+    IoError(std::io::Error),
+    UnsupportedFeature(String),
+    LimitsExceeded,
+    ParseError {
+        line: usize,
+        column: usize,
+        message: String,
+    },
 }
 
-impl Error for PetriError {
+impl PetriError {
     fn description(&self) -> &str {
         match self {
             PetriError::BipartitionViolation => "Bipartition Violation: Edges cannot lead to identical Node types. They are only allowed from places to transitions or vice versa",
             PetriError::PlaceNotFound => "Place Not Found: There is no corresponding place in the internal representation",
             PetriError::TransitionNotFound => "Transition Not Found: There is no corresponding transition in the internal representation",
             PetriError::ArcNotFound => "Arc Not Found: There is no corresponding arc in the internal representation",
+            PetriError::TransitionNotEnabled => "Transition Not Enabled: The transition cannot fire in the given marking",
+            PetriError::DuplicateName(_) => "Duplicate Name: Another node already has this name but names are required to be unique",
             PetriError::ObjectNotFound => "Object Not Found: There is no corresponding object in the internal representation",
             PetriError::PageNotFound => "Page Not Found: Could not find (sub)page in the given path",
             PetriError::NetNotFound => "Net Not Found: Could not find the referenced petri net in the pnml document",
             PetriError::InvalidData(_) => "Invalid Data: Tried to use data in a place where it do not belong",
             PetriError::CorruptedData(_) => "Corrupted Data: There where objects in arrays there they shouldn't be",
-            PetriError::XmlWriterError(error) => error.description()
+            PetriError::XmlWriterError(_) => "Xml Writer Error: Could not write the xml document",
+            PetriError::XmlReaderError(_) => "Xml Reader Error: Could not read the xml document",
+            PetriError::IoError(_) => "Io Error: Could not read or write the document",
+            PetriError::UnsupportedFeature(_) => "Unsupported Feature: The net uses a feature that is not supported here",
+            PetriError::LimitsExceeded => "Limits Exceeded: The state space is larger than the exploration limits allow",
+            PetriError::ParseError { .. } => "Parse Error: The document does not match the expected syntax",
+        }
+    }
+}
+
+impl Error for PetriError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            PetriError::XmlWriterError(error) => Some(error),
+            PetriError::XmlReaderError(error) => Some(error),
+            PetriError::IoError(error) => Some(error),
+            _ => None,
         }
     }
 }
@@ -35,7 +64,25 @@ impl std::fmt::Display for PetriError {
         let msg = match self {
             PetriError::InvalidData(msg) => format!("{}: {}", self.description(), msg),
             PetriError::CorruptedData(msg) => format!("{}: {}", self.description(), msg),
-            _ => format!("{}", self.description()),
+            PetriError::DuplicateName(name) => format!("{}: {}", self.description(), name),
+            PetriError::UnsupportedFeature(feature) => {
+                format!("{}: {}", self.description(), feature)
+            }
+            PetriError::XmlWriterError(error) => format!("{}: {}", self.description(), error),
+            PetriError::XmlReaderError(error) => format!("{}: {}", self.description(), error),
+            PetriError::IoError(error) => format!("{}: {}", self.description(), error),
+            PetriError::ParseError {
+                line,
+                column,
+                message,
+            } => format!(
+                "{} (line {}, column {}): {}",
+                self.description(),
+                line,
+                column,
+                message
+            ),
+            _ => self.description().to_string(),
         };
         write!(f, "{}", msg)
     }
@@ -43,12 +90,7 @@ impl std::fmt::Display for PetriError {
 
 impl std::fmt::Debug for PetriError {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        let msg = match self {
-            PetriError::InvalidData(msg) => format!("{}: {}", self.description(), msg),
-            PetriError::CorruptedData(msg) => format!("{}: {}", self.description(), msg),
-            _ => format!("{}", self.description()),
-        };
-        write!(f, "{}", msg)
+        write!(f, "{}", self)
     }
 }
 
@@ -57,3 +99,15 @@ impl From<xml::writer::Error> for PetriError {
         PetriError::XmlWriterError(error)
     }
 }
+
+impl From<xml::reader::Error> for PetriError {
+    fn from(error: xml::reader::Error) -> Self {
+        PetriError::XmlReaderError(error)
+    }
+}
+
+impl From<std::io::Error> for PetriError {
+    fn from(error: std::io::Error) -> Self {
+        PetriError::IoError(error)
+    }
+}
@@ -0,0 +1,228 @@
+use crate::{ArcKind, PetriError, PetriNet, PlaceRef, Result, SparseMatrix, TransitionRef};
+use std::collections::HashMap;
+
+impl PetriNet {
+    /// Minimal semi-positive place invariants: weightings `y` of the places
+    /// with `y · C = 0` for the incidence matrix `C`. The weighted token sum
+    /// of every reachable marking equals the one of the initial marking.
+    /// Every semi-positive place invariant is a positive combination of the
+    /// returned ones.
+    /// Fails with `InvalidData` if intermediate weights overflow `i64`.
+    pub fn p_invariants(&self) -> Result<Vec<HashMap<PlaceRef, i64>>> {
+        let matrix = self.invariant_matrix()?;
+        let incidence = dense(&matrix);
+        Ok(farkas(&incidence)?
+            .into_iter()
+            .map(|invariant| {
+                invariant
+                    .into_iter()
+                    .map(|(row, weight)| (matrix.places()[row], weight))
+                    .collect()
+            })
+            .collect())
+    }
+
+    /// Minimal semi-positive transition invariants: firing counts `x` of the
+    /// transitions with `C · x = 0`. Firing a sequence with these counts
+    /// reproduces the marking it started in.
+    /// Fails with `InvalidData` if intermediate weights overflow `i64`.
+    pub fn t_invariants(&self) -> Result<Vec<HashMap<TransitionRef, i64>>> {
+        let matrix = self.invariant_matrix()?;
+        let incidence = transpose(&dense(&matrix), matrix.transitions().len());
+        Ok(farkas(&incidence)?
+            .into_iter()
+            .map(|invariant| {
+                invariant
+                    .into_iter()
+                    .map(|(column, weight)| (matrix.transitions()[column], weight))
+                    .collect()
+            })
+            .collect())
+    }
+
+    /// true if the token sum weighted by `weights` is the same in every
+    /// marking, places that are missing have weight zero
+    pub fn is_p_invariant(&self, weights: &HashMap<PlaceRef, i64>) -> Result<bool> {
+        for place in weights.keys() {
+            self.place(*place)?;
+        }
+        let matrix = self.invariant_matrix()?;
+        for transition in matrix.transitions() {
+            let products = weights
+                .iter()
+                .map(|(place, weight)| weight.checked_mul(matrix.get(*place, *transition)));
+            if checked_sum(products)? != 0 {
+                return Ok(false);
+            }
+        }
+        Ok(true)
+    }
+
+    /// true if firing every transition as often as given by `weights`
+    /// does not change the marking, missing transitions fire zero times
+    pub fn is_t_invariant(&self, weights: &HashMap<TransitionRef, i64>) -> Result<bool> {
+        for transition in weights.keys() {
+            self.transition(*transition)?;
+        }
+        let matrix = self.invariant_matrix()?;
+        for place in matrix.places() {
+            let products = weights
+                .iter()
+                .map(|(transition, weight)| weight.checked_mul(matrix.get(*place, *transition)));
+            if checked_sum(products)? != 0 {
+                return Ok(false);
+            }
+        }
+        Ok(true)
+    }
+
+    /// Reset arcs remove tokens that the incidence matrix does not account
+    /// for, invariants of nets with reset arcs would not be invariant.
+    fn invariant_matrix(&self) -> Result<SparseMatrix> {
+        if self.uses_arc_kind(ArcKind::Reset) {
+            return Err(PetriError::UnsupportedFeature(
+                "invariants of nets with reset arcs".into(),
+            ));
+        }
+        self.incidence_matrix()
+    }
+}
+
+/// rows and columns in the order of the places and transitions of the matrix
+fn dense(matrix: &SparseMatrix) -> Vec<Vec<i64>> {
+    matrix
+        .places()
+        .iter()
+        .map(|place| {
+            matrix
+                .transitions()
+                .iter()
+                .map(|transition| matrix.get(*place, *transition))
+                .collect()
+        })
+        .collect()
+}
+
+fn transpose(matrix: &[Vec<i64>], columns: usize) -> Vec<Vec<i64>> {
+    (0..columns)
+        .map(|column| matrix.iter().map(|row| row[column]).collect())
+        .collect()
+}
+
+fn overflow() -> PetriError {
+    PetriError::InvalidData("the invariant weights overflow 64 bit integers".into())
+}
+
+/// sum of products that are `None` if they overflowed
+fn checked_sum(mut products: impl Iterator<Item = Option<i64>>) -> Result<i64> {
+    products.try_fold(0i64, |sum, product| {
+        product
+            .and_then(|product| sum.checked_add(product))
+            .ok_or_else(overflow)
+    })
+}
+
+/// Farkas algorithm: minimal semi-positive solutions `y` of `y · matrix = 0`
+/// as sparse (row index, weight) lists.
+fn farkas(matrix: &[Vec<i64>]) -> Result<Vec<Vec<(usize, i64)>>> {
+    let rows = matrix.len();
+    let columns = matrix.first().map_or(0, Vec::len);
+    // each row is the matrix row followed by the identity part that tracks
+    // the combination of original rows
+    let mut table: Vec<Vec<i64>> = matrix
+        .iter()
+        .enumerate()
+        .map(|(index, row)| {
+            let mut extended = row.clone();
+            extended.extend((0..rows).map(|i| if i == index { 1 } else { 0 }));
+            extended
+        })
+        .collect();
+    for column in 0..columns {
+        let mut next: Vec<Vec<i64>> = table
+            .iter()
+            .filter(|row| row[column] == 0)
+            .cloned()
+            .collect();
+        for (i, first) in table.iter().enumerate() {
+            for second in table.iter().skip(i + 1) {
+                if first[column].signum() * second[column].signum() < 0 {
+                    let a = second[column].abs();
+                    let b = first[column].abs();
+                    let combined = first
+                        .iter()
+                        .zip(second.iter())
+                        .map(|(x, y)| {
+                            a.checked_mul(*x)
+                                .zip(b.checked_mul(*y))
+                                .and_then(|(x, y)| x.checked_add(y))
+                                // keeps `abs` from overflowing later on
+                                .filter(|sum| *sum != i64::MIN)
+                                .ok_or_else(overflow)
+                        })
+                        .collect::<Result<Vec<i64>>>()?;
+                    next.push(normalize(combined));
+                }
+            }
+        }
+        table = minimal_supports(next, columns);
+    }
+    Ok(table
+        .into_iter()
+        .map(|row| {
+            row[columns..]
+                .iter()
+                .enumerate()
+                .filter(|(_, weight)| **weight != 0)
+                .map(|(index, weight)| (index, *weight))
+                .collect()
+        })
+        .collect())
+}
+
+fn normalize(row: Vec<i64>) -> Vec<i64> {
+    let divisor = row.iter().fold(0, |acc, x| gcd(acc, x.abs()));
+    if divisor > 1 {
+        row.into_iter().map(|x| x / divisor).collect()
+    } else {
+        row
+    }
+}
+
+fn gcd(a: i64, b: i64) -> i64 {
+    if b == 0 {
+        a
+    } else {
+        gcd(b, a % b)
+    }
+}
+
+/// remove rows whose support in the identity part contains the support of
+/// another row, they are combinations of smaller invariants
+fn minimal_supports(table: Vec<Vec<i64>>, offset: usize) -> Vec<Vec<i64>> {
+    let supports: Vec<Vec<usize>> = table
+        .iter()
+        .map(|row| {
+            row[offset..]
+                .iter()
+                .enumerate()
+                .filter(|(_, x)| **x != 0)
+                .map(|(index, _)| index)
+                .collect()
+        })
+        .collect();
+    let contains = |outer: &[usize], inner: &[usize]| inner.iter().all(|i| outer.contains(i));
+    table
+        .into_iter()
+        .enumerate()
+        .filter(|(i, _)| {
+            !supports.iter().enumerate().any(|(j, other)| {
+                j != *i
+                    && contains(&supports[*i], other)
+                    // of two rows with equal support only the first one stays
+                    && (supports[*i].len() > other.len() || j < *i)
+            })
+        })
+        .map(|(_, row)| row)
+        .collect()
+}
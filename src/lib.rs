@@ -1,48 +1,94 @@
 extern crate xml;
 
+mod capacity;
+mod coloured;
+mod coverability;
+mod ctl;
+mod ctmc;
 mod data;
+mod deadlock;
 mod dot;
 mod error;
+mod invariants;
+mod liveness;
 mod lola;
+mod ltl;
+mod marking;
+mod matrix;
+mod names;
 mod pnml;
+mod priority;
+mod property;
+mod reachability;
+mod stochastic;
+mod time;
+mod tina;
+mod validate;
 
 use log::info;
 
-use std::collections::{HashMap, HashSet};
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
 use std::convert::TryFrom;
 use std::hash::Hash;
 
+pub use data::ArcKind;
 use data::{Arc, Place, Transition};
-use error::PetriError;
 
 pub type Result<T> = std::result::Result<T, PetriError>;
-pub use crate::dot::*;
-pub use crate::lola::*;
-pub use crate::pnml::*;
+pub use crate::coloured::{
+    ArcExpression, Colour, ColouredNet, ColouredPlaceRef, ColouredTransitionRef, Guard, Sort,
+    SortRef, Term, VariableRef,
+};
+pub use crate::coverability::{CoverabilityTree, OmegaMarking, Tokens, UnboundedWitness};
+pub use crate::ctl::{Atom, Comparison, Ctl, Lasso, Verdict, Witness};
+pub use crate::ctmc::Ctmc;
+pub use crate::deadlock::{DeadlockSearch, FiringSequence};
+pub use crate::error::PetriError;
+pub use crate::liveness::Liveness;
+pub use crate::ltl::Ltl;
+pub use crate::marking::Marking;
+pub use crate::matrix::SparseMatrix;
+pub use crate::property::Property;
+pub use crate::reachability::{Edge, ExplorationLimits, ReachabilityGraph, SearchOrder};
+pub use crate::stochastic::{SimulationLimits, Step, StochasticKind, Trajectory};
+pub use crate::time::{StateClass, StateClassGraph, TimeInterval};
 
+/// arcs as (source, sink, multiplicity) triples
+pub(crate) type ArcTriples = Vec<(NodeRef, NodeRef, usize)>;
+
+/// Removed nodes and arcs leave an empty slot behind. Slots are never reused,
+/// so references to removed elements stay invalid instead of pointing to
+/// elements that were added later.
 #[derive(Debug, Clone)]
 pub struct PetriNet {
-    places: Vec<Place>,
-    transitions: Vec<Transition>,
-    arcs: Vec<Arc>,
+    places: Vec<Option<Place>>,
+    transitions: Vec<Option<Transition>>,
+    arcs: Vec<Option<Arc>>,
+    /// nodes by name, a name can belong to several nodes unless `unique_names` is set
+    names: HashMap<String, Vec<NodeRef>>,
+    unique_names: bool,
+    /// exporters use node names instead of indices as identifiers
+    name_ids: bool,
+    /// (higher, lower) pairs, see `add_priority`
+    priorities: BTreeSet<(TransitionRef, TransitionRef)>,
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub struct PlaceRef {
     index: usize,
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub struct TransitionRef {
     index: usize,
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub struct ArcRef {
     index: usize,
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub enum NodeRef {
     Place(PlaceRef),
     Transition(TransitionRef),
@@ -54,75 +100,333 @@ impl PetriNet {
             places: Vec::new(),
             transitions: Vec::new(),
             arcs: Vec::new(),
+            names: HashMap::new(),
+            unique_names: false,
+            name_ids: false,
+            priorities: BTreeSet::new(),
         }
     }
 
     pub fn add_place(&mut self) -> NodeRef {
-        self.places.push(Place {
+        self.places.push(Some(Place {
             name: None,
             marking: 0,
-            preset: HashMap::new(),
-            postset: HashMap::new(),
-        });
+            capacity: None,
+            preset: BTreeMap::new(),
+            postset: BTreeMap::new(),
+            special: BTreeMap::new(),
+        }));
         NodeRef::Place(PlaceRef {
             index: self.places.len() - 1,
         })
     }
     pub fn add_transition(&mut self) -> NodeRef {
-        self.transitions.push(Transition {
+        self.transitions.push(Some(Transition {
             name: None,
-            preset: HashMap::new(),
-            postset: HashMap::new(),
-        });
+            interval: None,
+            stochastic: None,
+            preset: BTreeMap::new(),
+            postset: BTreeMap::new(),
+            special: BTreeMap::new(),
+        }));
         NodeRef::Transition(TransitionRef {
             index: self.transitions.len() - 1,
         })
     }
 
     pub fn add_arc(&mut self, source: NodeRef, sink: NodeRef) -> Result<ArcRef> {
+        self.add_weighted_arc(source, sink, 1)
+    }
+
+    /// Connects two nodes with an arc that consumes or produces `mult` tokens.
+    /// Parallel arcs are merged: if the nodes are already connected, `mult` is
+    /// added to the existing arc and its reference is returned.
+    pub fn add_weighted_arc(
+        &mut self,
+        source: NodeRef,
+        sink: NodeRef,
+        mult: usize,
+    ) -> Result<ArcRef> {
+        self.add_arc_of_kind(source, sink, mult, ArcKind::Normal)
+    }
+
+    /// Adds an arc that disables `transition` as long as `place` holds
+    /// `threshold` or more tokens, a threshold of one tests for emptiness.
+    /// An existing inhibitor arc between the nodes keeps the lower threshold.
+    pub fn add_inhibitor_arc(
+        &mut self,
+        place: NodeRef,
+        transition: NodeRef,
+        threshold: usize,
+    ) -> Result<ArcRef> {
+        self.add_arc_of_kind(place, transition, threshold, ArcKind::Inhibitor)
+    }
+
+    /// Adds an arc that only enables `transition` if `place` holds at least
+    /// `weight` tokens without consuming them.
+    /// An existing read arc between the nodes keeps the higher weight.
+    pub fn add_read_arc(
+        &mut self,
+        place: NodeRef,
+        transition: NodeRef,
+        weight: usize,
+    ) -> Result<ArcRef> {
+        self.add_arc_of_kind(place, transition, weight, ArcKind::Read)
+    }
+
+    /// Adds an arc that removes all tokens from `place` when `transition`
+    /// fires, it does not restrict when the transition is enabled.
+    pub fn add_reset_arc(&mut self, place: NodeRef, transition: NodeRef) -> Result<ArcRef> {
+        self.add_arc_of_kind(place, transition, 1, ArcKind::Reset)
+    }
+
+    /// Arcs other than normal ones have to lead from a place to a transition.
+    pub(crate) fn add_arc_of_kind(
+        &mut self,
+        source: NodeRef,
+        sink: NodeRef,
+        mult: usize,
+        kind: ArcKind,
+    ) -> Result<ArcRef> {
+        if kind != ArcKind::Normal {
+            PlaceRef::try_from(source)?;
+            TransitionRef::try_from(sink)?;
+        }
         check_bipartition(source, sink)?;
-        self.arcs.push(Arc {
+        self.check_node(source)?;
+        self.check_node(sink)?;
+        check_multiplicity(mult)?;
+        check_reset_weight(kind, mult)?;
+        if let Some(arc) = self.find_arc_of_kind(source, sink, kind) {
+            let arc_data = self.arc_mut(arc)?;
+            arc_data.mult = kind.merge(arc_data.mult, mult);
+            return Ok(arc);
+        }
+        self.arcs.push(Some(Arc {
             name: None,
             source,
             sink,
-            mult: 1,
-        });
-        source.add_to_postset(self, sink)?;
-        sink.add_to_preset(self, source)?;
-        Ok(ArcRef {
+            mult,
+            kind,
+        }));
+        let arc = ArcRef {
             index: self.arcs.len() - 1,
-        })
+        };
+        self.connect(source, sink, kind, Some(arc))?;
+        Ok(arc)
+    }
+
+    /// the token moving arc leading from `source` to `sink` if there is one
+    pub fn find_arc(&self, source: NodeRef, sink: NodeRef) -> Option<ArcRef> {
+        self.find_arc_of_kind(source, sink, ArcKind::Normal)
+    }
+
+    pub fn find_arc_of_kind(
+        &self,
+        source: NodeRef,
+        sink: NodeRef,
+        kind: ArcKind,
+    ) -> Option<ArcRef> {
+        let transition = match (source, sink) {
+            (NodeRef::Place(_), NodeRef::Transition(transition)) => transition,
+            (NodeRef::Transition(transition), NodeRef::Place(_)) => transition,
+            _ => return None,
+        };
+        let transition = self.transition(transition).ok()?;
+        match (source, sink, kind) {
+            (NodeRef::Place(place), _, ArcKind::Normal) => transition.preset.get(&place),
+            (NodeRef::Transition(_), NodeRef::Place(place), ArcKind::Normal) => {
+                transition.postset.get(&place)
+            }
+            (NodeRef::Place(place), _, kind) => transition.special.get(&(place, kind)),
+            _ => None,
+        }
+        .copied()
+    }
+
+    /// set (or clear if `arc` is `None`) the pre- and postset entries of an arc
+    fn connect(
+        &mut self,
+        source: NodeRef,
+        sink: NodeRef,
+        kind: ArcKind,
+        arc: Option<ArcRef>,
+    ) -> Result<()> {
+        match (source, sink, kind) {
+            (NodeRef::Place(place), NodeRef::Transition(transition), ArcKind::Normal) => {
+                set_entry(&mut self.place_mut(place)?.postset, transition, arc);
+                set_entry(&mut self.transition_mut(transition)?.preset, place, arc);
+            }
+            (NodeRef::Transition(transition), NodeRef::Place(place), ArcKind::Normal) => {
+                set_entry(&mut self.transition_mut(transition)?.postset, place, arc);
+                set_entry(&mut self.place_mut(place)?.preset, transition, arc);
+            }
+            (NodeRef::Place(place), NodeRef::Transition(transition), kind) => {
+                set_entry(&mut self.place_mut(place)?.special, (transition, kind), arc);
+                set_entry(
+                    &mut self.transition_mut(transition)?.special,
+                    (place, kind),
+                    arc,
+                );
+            }
+            _ => return Err(PetriError::BipartitionViolation),
+        }
+        Ok(())
+    }
+
+    /// pre- or postset with the multiplicities of the connecting arcs
+    fn weighted<K: Ord + Copy>(&self, set: &BTreeMap<K, ArcRef>) -> Result<BTreeMap<K, usize>> {
+        set.iter()
+            .map(|(node, arc)| Ok((*node, self.arc(*arc)?.mult)))
+            .collect()
     }
 
     /// partition the arcs in transition -> place and place -> transition arcs with
     /// the corresponding multiplicity
-    pub(crate) fn arcs_partitioned(
-        &self,
-    ) -> (
-        Vec<(NodeRef, NodeRef, usize)>,
-        Vec<(NodeRef, NodeRef, usize)>,
-    ) {
+    pub(crate) fn arcs_partitioned(&self) -> (ArcTriples, ArcTriples) {
         self.arcs
             .iter()
+            .flatten()
+            .filter(|arc| arc.kind == ArcKind::Normal)
             .map(|arc| (arc.source, arc.sink, arc.mult))
             .partition(|(source, _, _)| TransitionRef::try_from(*source).is_ok())
     }
 
+    pub(crate) fn uses_arc_kind(&self, kind: ArcKind) -> bool {
+        self.arcs.iter().flatten().any(|arc| arc.kind == kind)
+    }
+
+    /// Removes the arc and its contribution to the pre- and postsets of the
+    /// connected nodes.
+    pub fn remove_arc(&mut self, arc: ArcRef) -> Result<()> {
+        let removed = self
+            .arcs
+            .get_mut(arc.index)
+            .and_then(Option::take)
+            .ok_or(PetriError::ArcNotFound)?;
+        self.connect(removed.source, removed.sink, removed.kind, None)
+    }
+
+    /// Removes the place together with all arcs connected to it.
+    pub fn remove_place(&mut self, place: PlaceRef) -> Result<()> {
+        let name = self.place(place)?.name.clone();
+        self.remove_connected_arcs(NodeRef::Place(place))?;
+        self.places[place.index] = None;
+        self.unindex_name(NodeRef::Place(place), name);
+        Ok(())
+    }
+
+    /// Removes the transition together with all arcs and priorities
+    /// connected to it.
+    pub fn remove_transition(&mut self, transition: TransitionRef) -> Result<()> {
+        let name = self.transition(transition)?.name.clone();
+        self.remove_connected_arcs(NodeRef::Transition(transition))?;
+        self.priorities
+            .retain(|(higher, lower)| *higher != transition && *lower != transition);
+        self.transitions[transition.index] = None;
+        self.unindex_name(NodeRef::Transition(transition), name);
+        Ok(())
+    }
+
+    fn remove_connected_arcs(&mut self, node: NodeRef) -> Result<()> {
+        let connected: Vec<ArcRef> = self
+            .arcs()
+            .filter(|arc| {
+                let arc = self.arc(*arc).expect("arcs() only yields existing arcs");
+                arc.source == node || arc.sink == node
+            })
+            .collect();
+        for arc in connected {
+            self.remove_arc(arc)?;
+        }
+        Ok(())
+    }
+
+    pub fn places(&self) -> impl Iterator<Item = PlaceRef> + '_ {
+        self.places
+            .iter()
+            .enumerate()
+            .filter(|(_, place)| place.is_some())
+            .map(|(index, _)| PlaceRef { index })
+    }
+
+    pub fn transitions(&self) -> impl Iterator<Item = TransitionRef> + '_ {
+        self.transitions
+            .iter()
+            .enumerate()
+            .filter(|(_, transition)| transition.is_some())
+            .map(|(index, _)| TransitionRef { index })
+    }
+
+    pub fn arcs(&self) -> impl Iterator<Item = ArcRef> + '_ {
+        self.arcs
+            .iter()
+            .enumerate()
+            .filter(|(_, arc)| arc.is_some())
+            .map(|(index, _)| ArcRef { index })
+    }
+
+    pub(crate) fn place(&self, place: PlaceRef) -> Result<&Place> {
+        self.places
+            .get(place.index)
+            .and_then(Option::as_ref)
+            .ok_or(PetriError::PlaceNotFound)
+    }
+
+    fn place_mut(&mut self, place: PlaceRef) -> Result<&mut Place> {
+        self.places
+            .get_mut(place.index)
+            .and_then(Option::as_mut)
+            .ok_or(PetriError::PlaceNotFound)
+    }
+
+    pub(crate) fn transition(&self, transition: TransitionRef) -> Result<&Transition> {
+        self.transitions
+            .get(transition.index)
+            .and_then(Option::as_ref)
+            .ok_or(PetriError::TransitionNotFound)
+    }
+
+    fn transition_mut(&mut self, transition: TransitionRef) -> Result<&mut Transition> {
+        self.transitions
+            .get_mut(transition.index)
+            .and_then(Option::as_mut)
+            .ok_or(PetriError::TransitionNotFound)
+    }
+
+    pub(crate) fn arc(&self, arc: ArcRef) -> Result<&Arc> {
+        self.arcs
+            .get(arc.index)
+            .and_then(Option::as_ref)
+            .ok_or(PetriError::ArcNotFound)
+    }
+
+    fn arc_mut(&mut self, arc: ArcRef) -> Result<&mut Arc> {
+        self.arcs
+            .get_mut(arc.index)
+            .and_then(Option::as_mut)
+            .ok_or(PetriError::ArcNotFound)
+    }
+
+    fn check_node(&self, node: NodeRef) -> Result<()> {
+        match node {
+            NodeRef::Place(place) => self.place(place).map(|_| ()),
+            NodeRef::Transition(transition) => self.transition(transition).map(|_| ()),
+        }
+    }
+
     pub fn get_unconnected_nodes(&self) -> Result<HashSet<NodeRef>> {
         let mut set = HashSet::new();
-        for index in 0..self.transitions.len() {
-            let node = self
-                .transitions
-                .get(index)
-                .ok_or(PetriError::TransitionNotFound)?;
-            if node.preset.is_empty() && node.postset.is_empty() {
-                set.insert(NodeRef::Transition(TransitionRef { index }));
+        for transition in self.transitions() {
+            let node = self.transition(transition)?;
+            if node.preset.is_empty() && node.postset.is_empty() && node.special.is_empty() {
+                set.insert(NodeRef::Transition(transition));
             }
         }
-        for index in 0..self.places.len() {
-            let node = self.places.get(index).ok_or(PetriError::PlaceNotFound)?;
-            if node.preset.is_empty() && node.postset.is_empty() {
-                set.insert(NodeRef::Place(PlaceRef { index }));
+        for place in self.places() {
+            let node = self.place(place)?;
+            if node.preset.is_empty() && node.postset.is_empty() && node.special.is_empty() {
+                set.insert(NodeRef::Place(place));
             }
         }
         Ok(set)
@@ -137,22 +441,12 @@ impl PetriNet {
                 NodeRef::Place(place) => {
                     kind = "place";
                     index = place.index;
-                    name = self
-                        .places
-                        .get(index)
-                        .ok_or(PetriError::PlaceNotFound)?
-                        .name
-                        .as_ref();
+                    name = self.place(place)?.name.as_ref();
                 }
                 NodeRef::Transition(trans) => {
                     kind = "transition";
                     index = trans.index;
-                    name = self
-                        .transitions
-                        .get(index)
-                        .ok_or(PetriError::TransitionNotFound)?
-                        .name
-                        .as_ref();
+                    name = self.transition(trans)?.name.as_ref();
                 }
             }
             if let Some(name) = name {
@@ -165,140 +459,85 @@ impl PetriNet {
     }
 }
 
+impl Default for PetriNet {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl NodeRef {
+    /// Fails with `DuplicateName` if the net requires unique names and another
+    /// node already has this name.
     pub fn name(self, net: &mut PetriNet, name: String) -> Result<()> {
+        net.check_name(self, &name)?;
         let place = PlaceRef::try_from(self);
         let trans = TransitionRef::try_from(self);
         let node_name = if place.is_ok() {
-            &mut net
-                .places
-                .get_mut(place?.index)
-                .ok_or(PetriError::PlaceNotFound)?
-                .name
+            &mut net.place_mut(place?)?.name
         } else {
-            &mut net
-                .transitions
-                .get_mut(trans?.index)
-                .ok_or(PetriError::TransitionNotFound)?
-                .name
+            &mut net.transition_mut(trans?)?.name
         };
-        *node_name = Some(name);
-        Ok(())
-    }
-
-    pub fn add_to_preset(self, net: &mut PetriNet, node: NodeRef) -> Result<()> {
-        match self {
-            NodeRef::Place(place) => {
-                let place = net
-                    .places
-                    .get_mut(place.index)
-                    .ok_or(PetriError::PlaceNotFound)?;
-                let transition_index = TransitionRef::try_from(node)?;
-                if let Some(mult) = place.preset.insert(transition_index, 1) {
-                    place.preset.insert(transition_index, mult + 1);
-                };
-            }
-            NodeRef::Transition(transition) => {
-                let transition = net
-                    .transitions
-                    .get_mut(transition.index)
-                    .ok_or(PetriError::TransitionNotFound)?;
-                let place_index = PlaceRef::try_from(node)?;
-                if let Some(mult) = transition.preset.insert(place_index, 1) {
-                    transition.preset.insert(place_index, mult + 1);
-                };
-            }
-        }
-        Ok(())
-    }
-
-    pub fn add_to_postset(self, net: &mut PetriNet, node: NodeRef) -> Result<()> {
-        match self {
-            NodeRef::Place(place) => {
-                let place = net
-                    .places
-                    .get_mut(place.index)
-                    .ok_or(PetriError::PlaceNotFound)?;
-                let transition_index = TransitionRef::try_from(node)?;
-                if let Some(mult) = place.postset.insert(transition_index, 1) {
-                    place.postset.insert(transition_index, mult + 1);
-                };
-            }
-            NodeRef::Transition(transition) => {
-                let transition = net
-                    .transitions
-                    .get_mut(transition.index)
-                    .ok_or(PetriError::TransitionNotFound)?;
-                let place_index = PlaceRef::try_from(node)?;
-                if let Some(mult) = transition.postset.insert(place_index, 1) {
-                    transition.postset.insert(place_index, mult + 1);
-                };
-            }
-        }
+        let old_name = node_name.replace(name.clone());
+        net.unindex_name(self, old_name);
+        net.names.entry(name).or_default().push(self);
         Ok(())
     }
 }
 
 impl PlaceRef {
+    /// Fails with `InvalidData` if the marking exceeds the capacity of the place.
     pub fn marking(self, net: &mut PetriNet, marking: usize) -> Result<()> {
-        net.places
-            .get_mut(self.index)
-            .ok_or(PetriError::PlaceNotFound)?
-            .marking = marking;
+        let place = net.place_mut(self)?;
+        check_capacity(marking, place.capacity)?;
+        place.marking = marking;
         Ok(())
     }
 
-    pub fn preset<'net>(&self, net: &'net PetriNet) -> Result<&'net HashMap<TransitionRef, usize>> {
-        Ok(&net
-            .places
-            .get(self.index)
-            .ok_or(PetriError::PlaceNotFound)?
-            .preset)
+    /// Limits the tokens on the place, transitions are only enabled if firing
+    /// them does not exceed the capacity. `None` removes the limit.
+    /// Fails with `InvalidData` if the initial marking exceeds the capacity.
+    pub fn capacity(self, net: &mut PetriNet, capacity: Option<usize>) -> Result<()> {
+        let place = net.place_mut(self)?;
+        check_capacity(place.marking, capacity)?;
+        place.capacity = capacity;
+        Ok(())
     }
 
-    pub fn postset<'net>(
-        &self,
-        net: &'net PetriNet,
-    ) -> Result<&'net HashMap<TransitionRef, usize>> {
-        Ok(&net
-            .places
-            .get(self.index)
-            .ok_or(PetriError::PlaceNotFound)?
-            .postset)
+    /// transitions that produce tokens on this place with the produced amount
+    pub fn preset(&self, net: &PetriNet) -> Result<BTreeMap<TransitionRef, usize>> {
+        net.weighted(&net.place(*self)?.preset)
+    }
+
+    /// transitions that consume tokens from this place with the consumed amount
+    pub fn postset(&self, net: &PetriNet) -> Result<BTreeMap<TransitionRef, usize>> {
+        net.weighted(&net.place(*self)?.postset)
     }
 }
 
 impl TransitionRef {
-    pub fn preset<'net>(&self, net: &'net PetriNet) -> Result<&'net HashMap<PlaceRef, usize>> {
-        Ok(&net
-            .transitions
-            .get(self.index)
-            .ok_or(PetriError::TransitionNotFound)?
-            .preset)
+    /// places this transition consumes from with the consumed amount
+    pub fn preset(&self, net: &PetriNet) -> Result<BTreeMap<PlaceRef, usize>> {
+        net.weighted(&net.transition(*self)?.preset)
     }
 
-    pub fn postset<'net>(&self, net: &'net PetriNet) -> Result<&'net HashMap<PlaceRef, usize>> {
-        Ok(&net
-            .transitions
-            .get(self.index)
-            .ok_or(PetriError::TransitionNotFound)?
-            .postset)
+    /// places this transition produces on with the produced amount
+    pub fn postset(&self, net: &PetriNet) -> Result<BTreeMap<PlaceRef, usize>> {
+        net.weighted(&net.transition(*self)?.postset)
     }
 }
 
 impl ArcRef {
     pub fn name(self, net: &mut PetriNet, name: String) -> Result<()> {
-        net.arcs
-            .get_mut(self.index)
-            .ok_or(PetriError::ArcNotFound)?
-            .name = Some(name);
+        net.arc_mut(self)?.name = Some(name);
         Ok(())
     }
+    /// Fails with `InvalidData` for zero and for weights other than one on
+    /// reset arcs.
     pub fn multiplicity(self, net: &mut PetriNet, mult: usize) -> Result<()> {
-        net.arcs
-            .get_mut(self.index)
-            .ok_or(PetriError::ArcNotFound)?
-            .mult = mult;
+        check_multiplicity(mult)?;
+        let arc = net.arc_mut(self)?;
+        check_reset_weight(arc.kind, mult)?;
+        arc.mult = mult;
         Ok(())
     }
 }
@@ -329,6 +568,42 @@ impl TryFrom<NodeRef> for PlaceRef {
     }
 }
 
+fn set_entry<K: Ord>(set: &mut BTreeMap<K, ArcRef>, node: K, arc: Option<ArcRef>) {
+    match arc {
+        Some(arc) => set.insert(node, arc),
+        None => set.remove(&node),
+    };
+}
+
+fn check_multiplicity(mult: usize) -> Result<()> {
+    if mult == 0 {
+        return Err(PetriError::InvalidData(
+            "arcs have to move at least one token".into(),
+        ));
+    }
+    Ok(())
+}
+
+/// reset arcs remove all tokens, a weight would have no meaning
+fn check_reset_weight(kind: ArcKind, mult: usize) -> Result<()> {
+    if kind == ArcKind::Reset && mult != 1 {
+        return Err(PetriError::InvalidData(
+            "reset arcs have a weight of one".into(),
+        ));
+    }
+    Ok(())
+}
+
+fn check_capacity(marking: usize, capacity: Option<usize>) -> Result<()> {
+    match capacity {
+        Some(capacity) if marking > capacity => Err(PetriError::InvalidData(format!(
+            "a marking of {} exceeds the capacity of {}",
+            marking, capacity
+        ))),
+        _ => Ok(()),
+    }
+}
+
 fn check_bipartition(a: NodeRef, b: NodeRef) -> Result<()> {
     match a {
         NodeRef::Place(_) => match b {
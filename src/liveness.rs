@@ -0,0 +1,64 @@
+use crate::{ExplorationLimits, PetriError, PetriNet, Result, TransitionRef};
+use std::collections::{BTreeMap, BTreeSet};
+
+/// Behaviour of a transition in the reachable markings of a net.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Liveness {
+    /// never enabled in a reachable marking (L0)
+    Dead,
+    /// enabled in at least one reachable marking (L1)
+    QuasiLive,
+    /// can be enabled again from every reachable marking (L4)
+    Live,
+}
+
+impl PetriNet {
+    /// Classifies every transition by its liveness. A transition is live if
+    /// it fires inside every strongly connected component that cannot be
+    /// left, every reachable marking leads into one of them.
+    /// The reachability graph is built with the given limits, it fails with
+    /// `LimitsExceeded` if the graph is not complete.
+    pub fn liveness(
+        &self,
+        limits: &ExplorationLimits,
+    ) -> Result<BTreeMap<TransitionRef, Liveness>> {
+        let graph = self.reachability_graph(limits)?;
+        if !graph.is_complete() {
+            return Err(PetriError::LimitsExceeded);
+        }
+        let mut report: BTreeMap<TransitionRef, Liveness> = self
+            .transitions()
+            .map(|transition| (transition, Liveness::Dead))
+            .collect();
+        for edge in graph.edges() {
+            report.insert(edge.transition, Liveness::QuasiLive);
+        }
+        let components = graph.strongly_connected_components();
+        let mut component_of = vec![0; graph.markings().len()];
+        for (index, component) in components.iter().enumerate() {
+            for state in component {
+                component_of[*state] = index;
+            }
+        }
+        // transitions that fire in every bottom component
+        let mut live: Option<BTreeSet<TransitionRef>> = None;
+        for (index, component) in components.iter().enumerate() {
+            let edges: Vec<_> = component
+                .iter()
+                .flat_map(|state| graph.successors(*state))
+                .collect();
+            if edges.iter().any(|edge| component_of[edge.target] != index) {
+                continue;
+            }
+            let fired: BTreeSet<TransitionRef> = edges.iter().map(|edge| edge.transition).collect();
+            live = Some(match live {
+                Some(live) => live.intersection(&fired).copied().collect(),
+                None => fired,
+            });
+        }
+        for transition in live.unwrap_or_default() {
+            report.insert(transition, Liveness::Live);
+        }
+        Ok(report)
+    }
+}
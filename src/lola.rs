@@ -1,6 +1,16 @@
-use crate::{PetriNet, TransitionRef};
+use crate::names::NodeIds;
+use crate::{
+    ArcKind, Atom, Comparison, Ctl, Ltl, NodeRef, PetriError, PetriNet, PlaceRef, Property,
+};
+use std::collections::{BTreeMap, HashMap};
+use std::convert::TryFrom;
+
+const PLACE_PREFIX: &str = "p_";
+const TRANSITION_PREFIX: &str = "t_";
 
 impl PetriNet {
+    /// Fails with `InvalidInput` if the net uses features that LoLA does not
+    /// support, the inner error is a `PetriError::UnsupportedFeature`.
     pub fn to_lola_string(&self) -> Result<String, std::io::Error> {
         let mut writer = Vec::new();
         self.write_lola(&mut writer)?;
@@ -18,77 +28,275 @@ impl PetriNet {
         Ok(())
     }
 
+    /// The property in LoLA's formula syntax, with the same identifiers as
+    /// `to_lola` so that net and formula agree on the names of the nodes.
+    /// Fails with `InvalidInput` if the property refers to nodes that are not
+    /// in the net, or uses `Ltl::Fired` which LoLA has no syntax for.
+    pub fn to_lola_formula_string(&self, property: &Property) -> Result<String, std::io::Error> {
+        let mut writer = Vec::new();
+        self.to_lola_formula(property, &mut writer)?;
+        Ok(String::from_utf8(writer).expect("Document generated non UTF-8 string"))
+    }
+
+    /// ```text
+    /// EF (p_0 >= 2 AND FIREABLE(t_1))
+    /// ```
+    pub fn to_lola_formula<T>(
+        &self,
+        property: &Property,
+        writer: &mut T,
+    ) -> Result<(), std::io::Error>
+    where
+        T: std::io::Write,
+    {
+        let ids = self.node_ids(PLACE_PREFIX, TRANSITION_PREFIX);
+        let formula = match property {
+            Property::Reachable(formula) => {
+                format!("EF {}", self.lola_ctl(&ids, formula, true)?)
+            }
+            Property::Invariant(formula) => {
+                format!("AG {}", self.lola_ctl(&ids, formula, true)?)
+            }
+            Property::Ctl(formula) => self.lola_ctl(&ids, formula, false)?,
+            // LTL formulas are quantified over all paths
+            Property::Ltl(formula) => format!("A {}", self.lola_ltl(&ids, formula, true)?),
+        };
+        writer.write_all(format!("{}\n", formula).as_bytes())
+    }
+
+    fn lola_atom(
+        &self,
+        ids: &NodeIds,
+        atom: &Atom,
+        operand: bool,
+    ) -> Result<String, std::io::Error> {
+        Ok(match atom {
+            Atom::Tokens(place, comparison, tokens) => {
+                self.place(*place).map_err(invalid_input)?;
+                let operator = match comparison {
+                    Comparison::Less => "<",
+                    Comparison::LessOrEqual => "<=",
+                    Comparison::Equal => "=",
+                    Comparison::NotEqual => "!=",
+                    Comparison::GreaterOrEqual => ">=",
+                    Comparison::Greater => ">",
+                };
+                let comparison = format!("{} {} {}", ids.place(*place), operator, tokens);
+                if operand {
+                    format!("({})", comparison)
+                } else {
+                    comparison
+                }
+            }
+            Atom::Fireable(transition) => {
+                self.transition(*transition).map_err(invalid_input)?;
+                format!("FIREABLE({})", ids.transition(*transition))
+            }
+            Atom::Deadlock => "DEADLOCK".into(),
+        })
+    }
+
+    /// Operands of unary operators are put in parentheses unless they are
+    /// atomic, the operands of `AND` and `OR` only if they are connectives
+    /// themselves.
+    fn lola_ctl(
+        &self,
+        ids: &NodeIds,
+        formula: &Ctl,
+        operand: bool,
+    ) -> Result<String, std::io::Error> {
+        let unary = |operator: &str, formula: &Ctl| -> Result<String, std::io::Error> {
+            Ok(format!(
+                "{} {}",
+                operator,
+                self.lola_ctl(ids, formula, true)?
+            ))
+        };
+        let until = |quantifier: &str, hold: &Ctl, goal: &Ctl| -> Result<String, std::io::Error> {
+            Ok(format!(
+                "{} ({} U {})",
+                quantifier,
+                self.lola_ctl(ids, hold, true)?,
+                self.lola_ctl(ids, goal, true)?
+            ))
+        };
+        let connective = match formula {
+            Ctl::True => return Ok("TRUE".into()),
+            Ctl::False => return Ok("FALSE".into()),
+            Ctl::Atom(atom) => return self.lola_atom(ids, atom, operand),
+            Ctl::Not(formula) => return unary("NOT", formula),
+            Ctl::ExistsNext(formula) => return unary("EX", formula),
+            Ctl::AllNext(formula) => return unary("AX", formula),
+            Ctl::ExistsFinally(formula) => return unary("EF", formula),
+            Ctl::AllFinally(formula) => return unary("AF", formula),
+            Ctl::ExistsGlobally(formula) => return unary("EG", formula),
+            Ctl::AllGlobally(formula) => return unary("AG", formula),
+            Ctl::ExistsUntil(hold, goal) => return until("E", hold, goal),
+            Ctl::AllUntil(hold, goal) => return until("A", hold, goal),
+            Ctl::And(formulas) => ("AND", "TRUE", formulas),
+            Ctl::Or(formulas) => ("OR", "FALSE", formulas),
+        };
+        let (keyword, empty, formulas) = connective;
+        let operands = formulas
+            .iter()
+            .map(|formula| match formula {
+                Ctl::Atom(atom) => self.lola_atom(ids, atom, false),
+                _ => self.lola_ctl(ids, formula, true),
+            })
+            .collect::<Result<Vec<String>, std::io::Error>>()?;
+        Ok(join_lola(keyword, empty, operands, operand))
+    }
+
+    /// Release has no keyword in LoLA and is written as a negated until.
+    fn lola_ltl(
+        &self,
+        ids: &NodeIds,
+        formula: &Ltl,
+        operand: bool,
+    ) -> Result<String, std::io::Error> {
+        let unary = |operator: &str, formula: &Ltl| -> Result<String, std::io::Error> {
+            Ok(format!(
+                "{} {}",
+                operator,
+                self.lola_ltl(ids, formula, true)?
+            ))
+        };
+        let connective = match formula {
+            Ltl::True => return Ok("TRUE".into()),
+            Ltl::False => return Ok("FALSE".into()),
+            Ltl::Atom(atom) => return self.lola_atom(ids, atom, operand),
+            Ltl::Fired(_) => {
+                return Err(invalid_input(PetriError::UnsupportedFeature(
+                    "LoLA does not support fired transitions in formulas".into(),
+                )))
+            }
+            Ltl::Not(formula) => return unary("NOT", formula),
+            Ltl::Next(formula) => return unary("X", formula),
+            Ltl::Finally(formula) => return unary("F", formula),
+            Ltl::Globally(formula) => return unary("G", formula),
+            Ltl::Until(hold, goal) => {
+                return Ok(format!(
+                    "({} U {})",
+                    self.lola_ltl(ids, hold, true)?,
+                    self.lola_ltl(ids, goal, true)?
+                ))
+            }
+            Ltl::Release(release, hold) => {
+                let until = Ltl::Until(
+                    Box::new(Ltl::Not(release.clone())),
+                    Box::new(Ltl::Not(hold.clone())),
+                );
+                return unary("NOT", &until);
+            }
+            Ltl::And(formulas) => ("AND", "TRUE", formulas),
+            Ltl::Or(formulas) => ("OR", "FALSE", formulas),
+        };
+        let (keyword, empty, formulas) = connective;
+        let operands = formulas
+            .iter()
+            .map(|formula| match formula {
+                Ltl::Atom(atom) => self.lola_atom(ids, atom, false),
+                _ => self.lola_ltl(ids, formula, true),
+            })
+            .collect::<Result<Vec<String>, std::io::Error>>()?;
+        Ok(join_lola(keyword, empty, operands, operand))
+    }
+
     fn write_lola<T>(&self, writer: &mut T) -> Result<(), std::io::Error>
     where
         T: std::io::Write,
     {
-        self.write_lola_places(writer)?;
-        self.write_lola_markings(writer)?;
-        self.write_lola_transitions(writer)
+        // LoLA has no syntax for inhibitor and reset arcs
+        for (kind, name) in &[(ArcKind::Inhibitor, "inhibitor"), (ArcKind::Reset, "reset")] {
+            if self.uses_arc_kind(*kind) {
+                return Err(std::io::Error::new(
+                    std::io::ErrorKind::InvalidInput,
+                    PetriError::UnsupportedFeature(format!("LoLA does not support {} arcs", name)),
+                ));
+            }
+        }
+        if self.has_priorities() {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidInput,
+                PetriError::UnsupportedFeature("LoLA does not support priorities".into()),
+            ));
+        }
+        let ids = self.node_ids(PLACE_PREFIX, TRANSITION_PREFIX);
+        self.write_lola_places(writer, &ids)?;
+        self.write_lola_markings(writer, &ids)?;
+        self.write_lola_transitions(writer, &ids)
     }
 
-    /// ```
+    /// ```text
     /// PLACE
     ///     p_1,
     ///     ..
     ///     p_n;
+    ///   SAFE 2:
+    ///     p_m;
     /// ```
-    fn write_lola_places<T>(&self, writer: &mut T) -> Result<(), std::io::Error>
+    /// Places with a capacity are grouped by it.
+    fn write_lola_places<T>(&self, writer: &mut T, ids: &NodeIds) -> Result<(), std::io::Error>
     where
         T: std::io::Write,
     {
-        const PREFIX: &str = "p_";
-        if !self.places.is_empty() {
-            writer.write("PLACE\n".as_bytes())?;
-            if self.places.len() > 2 {
-                for place in 0..self.places.len() - 1 {
-                    let line = format!("    {},\n", make_id(PREFIX, place));
-                    writer.write(line.as_bytes())?;
+        let mut groups: BTreeMap<Option<usize>, Vec<String>> = BTreeMap::new();
+        for place in self.places() {
+            groups
+                .entry(self.place(place).unwrap().capacity)
+                .or_default()
+                .push(format!("    {}", ids.place(place)));
+        }
+        if !groups.is_empty() {
+            writer.write_all("PLACE\n".as_bytes())?;
+            for (capacity, places) in groups {
+                if let Some(capacity) = capacity {
+                    let line = format!("  SAFE {}:\n", capacity);
+                    writer.write_all(line.as_bytes())?;
                 }
-            };
-            // last line has a semicolon
-            let place = self.places.len() - 1;
-            let line = format!("    {};\n\n", make_id(PREFIX, place));
-            writer.write(line.as_bytes())?;
+                // last line of a group has a semicolon
+                let line = format!("{};\n", places.join(",\n"));
+                writer.write_all(line.as_bytes())?;
+            }
+            writer.write_all("\n".as_bytes())?;
         }
         Ok(())
     }
 
-    /// ```
+    /// ```text
     /// MARKING
     ///   p_5 : 4,
     ///   p_25 : 1;
     /// ```
-    fn write_lola_markings<T>(&self, writer: &mut T) -> Result<(), std::io::Error>
+    fn write_lola_markings<T>(&self, writer: &mut T, ids: &NodeIds) -> Result<(), std::io::Error>
     where
         T: std::io::Write,
     {
-        const PREFIX: &str = "p_";
-        if !self.places.is_empty() {
+        if self.places().next().is_some() {
             let mut first = true;
-            writer.write("MARKING\n".as_bytes())?;
-            for place in 0..self.places.len() {
-                let marking = self.places.get(place).unwrap().marking;
+            writer.write_all("MARKING\n".as_bytes())?;
+            for place in self.places() {
+                let marking = self.place(place).unwrap().marking;
                 let line = if marking > 0 {
                     // first line has no ',' at the front
                     if !first {
-                        format!(",\n    {} : {}", make_id(PREFIX, place), marking)
+                        format!(",\n    {} : {}", ids.place(place), marking)
                     } else {
                         first = false;
-                        format!("    {} : {}", make_id(PREFIX, place), marking)
+                        format!("    {} : {}", ids.place(place), marking)
                     }
                 } else {
                     String::new()
                 };
-                writer.write(line.as_bytes())?;
+                writer.write_all(line.as_bytes())?;
             }
             // last line has a semicolon
-            writer.write(";\n\n".as_bytes())?;
+            writer.write_all(";\n\n".as_bytes())?;
         }
         Ok(())
     }
 
-    /// ```
+    /// ```text
     /// TRANSITION
     ///   CONSUME
     ///     p_0 : 1,
@@ -99,57 +307,485 @@ impl PetriNet {
     /// TRANSITION
     /// ...
     /// ```
-    fn write_lola_transitions<T>(&self, writer: &mut T) -> Result<(), std::io::Error>
+    fn write_lola_transitions<T>(&self, writer: &mut T, ids: &NodeIds) -> Result<(), std::io::Error>
     where
         T: std::io::Write,
     {
-        const PREFIX: &str = "t_";
-        const PLACE_PREFIX: &str = "p_";
-        if !self.transitions.is_empty() {
-            for t in 0..self.transitions.len() {
-                let line = format!("TRANSITION {}\n", make_id(PREFIX, t));
-                writer.write(line.as_bytes())?;
-                let mut consume = TransitionRef { index: t }
-                    .preset(self)
-                    .expect("error in preset")
-                    .iter();
-                if let Some(first) = consume.next() {
-                    writer.write("  CONSUME\n".as_bytes())?;
-                    writer.write(
-                        format!("    {}{} : {}", PLACE_PREFIX, first.0.index, first.1,).as_bytes(),
-                    )?;
-                    for (place, mult) in consume {
-                        writer.write(
-                            format!(",\n    {}{} : {}", PLACE_PREFIX, place.index, mult,)
-                                .as_bytes(),
-                        )?;
+        for transition in self.transitions() {
+            let line = format!("TRANSITION {}\n", ids.transition(transition));
+            writer.write_all(line.as_bytes())?;
+            let mut consume = transition.preset(self).expect("error in preset");
+            let mut produce = transition.postset(self).expect("error in postset");
+            // a read arc consumes and produces the tokens it needs, together
+            // with a normal arc at least the larger weight has to be present
+            let read = self
+                .special_weights(transition, ArcKind::Read)
+                .expect("error in read arcs");
+            for (place, weight) in read {
+                let consumed = consume.entry(place).or_insert(0);
+                if *consumed < weight {
+                    *produce.entry(place).or_insert(0) += weight - *consumed;
+                    *consumed = weight;
+                }
+            }
+            write_lola_arcs(writer, ids, "CONSUME", consume.iter())?;
+            write_lola_arcs(writer, ids, "PRODUCE", produce.iter())?;
+        }
+        Ok(())
+    }
+}
+
+/// ```text
+///   CONSUME
+///     p_0 : 1,
+///     p_1 : 2;
+/// ```
+fn write_lola_arcs<'a, T, I>(
+    writer: &mut T,
+    ids: &NodeIds,
+    keyword: &str,
+    arcs: I,
+) -> Result<(), std::io::Error>
+where
+    T: std::io::Write,
+    I: Iterator<Item = (&'a PlaceRef, &'a usize)>,
+{
+    let arcs: Vec<String> = arcs
+        .map(|(place, mult)| format!("    {} : {}", ids.place(*place), mult))
+        .collect();
+    if !arcs.is_empty() {
+        writer.write_all(format!("  {}\n", keyword).as_bytes())?;
+        writer.write_all(format!("{};\n", arcs.join(",\n")).as_bytes())?;
+    }
+    Ok(())
+}
+
+/// operands joined by `AND` or `OR`
+fn join_lola(keyword: &str, empty: &str, operands: Vec<String>, operand: bool) -> String {
+    match operands.len() {
+        0 => empty.into(),
+        1 => operands.into_iter().next().unwrap(),
+        _ => {
+            let joined = operands.join(&format!(" {} ", keyword));
+            if operand {
+                format!("({})", joined)
+            } else {
+                joined
+            }
+        }
+    }
+}
+
+fn invalid_input(error: PetriError) -> std::io::Error {
+    std::io::Error::new(std::io::ErrorKind::InvalidInput, error)
+}
+
+impl PetriNet {
+    pub fn from_lola_string(document: &str) -> Result<Self, PetriError> {
+        LolaParser::new(document).parse_net()
+    }
+
+    /// Reads a net in LoLA's low level syntax.
+    /// The LoLA identifiers become the names of the nodes.
+    /// Capacities (`SAFE n:`) become capacities of the places, `SAFE:`
+    /// without a number stands for a capacity of one. Fairness assumptions
+    /// are accepted but not part of the resulting net.
+    pub fn from_lola<T>(mut reader: T) -> Result<Self, PetriError>
+    where
+        T: std::io::Read,
+    {
+        let mut document = String::new();
+        reader.read_to_string(&mut document)?;
+        Self::from_lola_string(&document)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Token {
+    Identifier(String),
+    Number(usize),
+    Comma,
+    Colon,
+    Semicolon,
+    End,
+}
+
+impl std::fmt::Display for Token {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Token::Identifier(identifier) => write!(f, "\"{}\"", identifier),
+            Token::Number(number) => write!(f, "\"{}\"", number),
+            Token::Comma => write!(f, "\",\""),
+            Token::Colon => write!(f, "\":\""),
+            Token::Semicolon => write!(f, "\";\""),
+            Token::End => write!(f, "end of document"),
+        }
+    }
+}
+
+/// Recursive descent parser for the low level net syntax:
+/// ```text
+/// PLACE
+///     SAFE 2: p_0, p_1;
+///     p_2;
+/// MARKING
+///     p_0 : 2, p_2;
+/// TRANSITION t_0 WEAK FAIR
+///   CONSUME p_0 : 1;
+///   PRODUCE p_1 : 1;
+/// ```
+/// Comments are enclosed in `{ }` or `/* */` or start with `//`.
+struct LolaParser<'a> {
+    chars: std::iter::Peekable<std::str::Chars<'a>>,
+    line: usize,
+    column: usize,
+    /// current token and its position
+    token: Token,
+    token_line: usize,
+    token_column: usize,
+    net: PetriNet,
+    places: HashMap<String, PlaceRef>,
+}
+
+impl<'a> LolaParser<'a> {
+    fn new(document: &'a str) -> Self {
+        LolaParser {
+            chars: document.chars().peekable(),
+            line: 1,
+            column: 1,
+            token: Token::End,
+            token_line: 1,
+            token_column: 1,
+            net: PetriNet::new(),
+            places: HashMap::new(),
+        }
+    }
+
+    fn error<R>(&self, message: String) -> Result<R, PetriError> {
+        Err(PetriError::ParseError {
+            line: self.token_line,
+            column: self.token_column,
+            message,
+        })
+    }
+
+    fn next_char(&mut self) -> Option<char> {
+        let c = self.chars.next()?;
+        if c == '\n' {
+            self.line += 1;
+            self.column = 1;
+        } else {
+            self.column += 1;
+        }
+        Some(c)
+    }
+
+    /// skip whitespace and comments
+    fn skip_trivia(&mut self) -> Result<(), PetriError> {
+        while let Some(&c) = self.chars.peek() {
+            if c.is_whitespace() {
+                self.next_char();
+            } else if c == '{' {
+                let (line, column) = (self.line, self.column);
+                self.next_char();
+                loop {
+                    match self.next_char() {
+                        Some('}') => break,
+                        Some(_) => {}
+                        None => {
+                            return Err(PetriError::ParseError {
+                                line,
+                                column,
+                                message: "unterminated comment".into(),
+                            })
+                        }
                     }
-                    writer.write(";\n".as_bytes())?;
                 }
-                let mut produce = TransitionRef { index: t }
-                    .postset(self)
-                    .expect("error in postsst")
-                    .iter();
-                if let Some(first) = produce.next() {
-                    writer.write("  PRODUCE\n".as_bytes())?;
-                    writer.write(
-                        format!("    {}{} : {}", PLACE_PREFIX, first.0.index, first.1,).as_bytes(),
-                    )?;
-                    for (place, mult) in produce {
-                        writer.write(
-                            format!(",\n    {}{} : {}", PLACE_PREFIX, place.index, mult,)
-                                .as_bytes(),
-                        )?;
+            } else if c == '/' {
+                let mut lookahead = self.chars.clone();
+                lookahead.next();
+                match lookahead.next() {
+                    Some('/') => {
+                        while let Some(c) = self.next_char() {
+                            if c == '\n' {
+                                break;
+                            }
+                        }
+                    }
+                    Some('*') => {
+                        let (line, column) = (self.line, self.column);
+                        self.next_char();
+                        self.next_char();
+                        let mut star = false;
+                        loop {
+                            match self.next_char() {
+                                Some('/') if star => break,
+                                Some(c) => star = c == '*',
+                                None => {
+                                    return Err(PetriError::ParseError {
+                                        line,
+                                        column,
+                                        message: "unterminated comment".into(),
+                                    })
+                                }
+                            }
+                        }
                     }
-                    writer.write(";\n".as_bytes())?;
+                    _ => break,
                 }
+            } else {
+                break;
             }
         }
         Ok(())
     }
+
+    fn advance(&mut self) -> Result<(), PetriError> {
+        self.skip_trivia()?;
+        self.token_line = self.line;
+        self.token_column = self.column;
+        self.token = match self.next_char() {
+            None => Token::End,
+            Some(',') => Token::Comma,
+            Some(':') => Token::Colon,
+            Some(';') => Token::Semicolon,
+            Some(c) => {
+                // identifiers are arbitrary character sequences up to the next delimiter
+                let mut word = c.to_string();
+                while let Some(&c) = self.chars.peek() {
+                    if c.is_whitespace() || ",:;{}".contains(c) {
+                        break;
+                    }
+                    // comments end identifiers as well
+                    if c == '/' {
+                        let mut lookahead = self.chars.clone();
+                        lookahead.next();
+                        if matches!(lookahead.next(), Some('/') | Some('*')) {
+                            break;
+                        }
+                    }
+                    self.next_char();
+                    word.push(c);
+                }
+                if word.chars().all(|c| c.is_ascii_digit()) {
+                    match word.parse() {
+                        Ok(number) => Token::Number(number),
+                        Err(_) => return self.error(format!("number {} is too large", word)),
+                    }
+                } else {
+                    Token::Identifier(word)
+                }
+            }
+        };
+        Ok(())
+    }
+
+    fn is_keyword(&self, keyword: &str) -> bool {
+        matches!(&self.token, Token::Identifier(word) if word == keyword)
+    }
+
+    fn expect(&mut self, expected: Token) -> Result<(), PetriError> {
+        if self.token != expected {
+            return self.error(format!("expected {} but found {}", expected, self.token));
+        }
+        self.advance()
+    }
+
+    fn expect_keyword(&mut self, keyword: &str) -> Result<(), PetriError> {
+        if !self.is_keyword(keyword) {
+            return self.error(format!("expected {} but found {}", keyword, self.token));
+        }
+        self.advance()
+    }
+
+    /// identifiers may also consist of digits only
+    fn identifier(&mut self) -> Result<String, PetriError> {
+        let identifier = match &self.token {
+            Token::Identifier(word) if !KEYWORDS.contains(&word.as_str()) => word.clone(),
+            Token::Number(number) => number.to_string(),
+            token => return self.error(format!("expected identifier but found {}", token)),
+        };
+        self.advance()?;
+        Ok(identifier)
+    }
+
+    fn number(&mut self) -> Result<usize, PetriError> {
+        match self.token {
+            Token::Number(number) => {
+                self.advance()?;
+                Ok(number)
+            }
+            _ => self.error(format!("expected number but found {}", self.token)),
+        }
+    }
+
+    fn place(&mut self) -> Result<PlaceRef, PetriError> {
+        let (line, column) = (self.token_line, self.token_column);
+        let identifier = self.identifier()?;
+        self.places
+            .get(&identifier)
+            .copied()
+            .ok_or(PetriError::ParseError {
+                line,
+                column,
+                message: format!("unknown place \"{}\"", identifier),
+            })
+    }
+
+    fn parse_net(mut self) -> Result<PetriNet, PetriError> {
+        self.advance()?;
+        // the empty net is written as an empty document
+        if self.token == Token::End {
+            return Ok(self.net);
+        }
+        self.parse_places()?;
+        self.parse_marking()?;
+        while self.is_keyword("TRANSITION") {
+            self.parse_transition()?;
+        }
+        if self.token != Token::End {
+            return self.error(format!("expected TRANSITION but found {}", self.token));
+        }
+        Ok(self.net)
+    }
+
+    /// `PLACE (SAFE [n] :)? p, ..; (SAFE [n] :)? p, ..;`
+    fn parse_places(&mut self) -> Result<(), PetriError> {
+        self.expect_keyword("PLACE")?;
+        loop {
+            let mut capacity = None;
+            if self.is_keyword("SAFE") {
+                self.advance()?;
+                capacity = Some(1);
+                if let Token::Number(_) = self.token {
+                    capacity = Some(self.number()?);
+                }
+                self.expect(Token::Colon)?;
+            }
+            loop {
+                let (line, column) = (self.token_line, self.token_column);
+                let identifier = self.identifier()?;
+                if self.places.contains_key(&identifier) {
+                    return Err(PetriError::ParseError {
+                        line,
+                        column,
+                        message: format!("place \"{}\" is declared twice", identifier),
+                    });
+                }
+                let place = self.net.add_place();
+                place.name(&mut self.net, identifier.clone())?;
+                let place_ref = PlaceRef::try_from(place)?;
+                place_ref.capacity(&mut self.net, capacity)?;
+                self.places.insert(identifier, place_ref);
+                if self.token != Token::Comma {
+                    break;
+                }
+                self.advance()?;
+            }
+            self.expect(Token::Semicolon)?;
+            if self.is_keyword("MARKING") {
+                return Ok(());
+            }
+        }
+    }
+
+    /// `MARKING p : n, p;` a place without a number holds one token
+    fn parse_marking(&mut self) -> Result<(), PetriError> {
+        self.expect_keyword("MARKING")?;
+        for (place, tokens, (line, column)) in self.parse_place_list()? {
+            if let Some(capacity) = self.net.place(place)?.capacity {
+                if tokens > capacity {
+                    return Err(PetriError::ParseError {
+                        line,
+                        column,
+                        message: format!(
+                            "{} tokens exceed the capacity {} of the place",
+                            tokens, capacity
+                        ),
+                    });
+                }
+            }
+            place.marking(&mut self.net, tokens)?;
+        }
+        Ok(())
+    }
+
+    /// `TRANSITION t [STRONG FAIR|WEAK FAIR] CONSUME p : n, ..; PRODUCE p : n, ..;`
+    fn parse_transition(&mut self) -> Result<(), PetriError> {
+        self.expect_keyword("TRANSITION")?;
+        let name = self.identifier()?;
+        let transition = self.net.add_transition();
+        transition.name(&mut self.net, name)?;
+        if self.is_keyword("STRONG") || self.is_keyword("WEAK") {
+            self.advance()?;
+            self.expect_keyword("FAIR")?;
+        }
+        // empty sections can be omitted
+        if self.is_keyword("CONSUME") {
+            self.advance()?;
+            for (place, mult, _) in self.parse_place_list()? {
+                if mult > 0 {
+                    let place = NodeRef::Place(place);
+                    self.net.add_weighted_arc(place, transition, mult)?;
+                }
+            }
+        }
+        if self.is_keyword("PRODUCE") {
+            self.advance()?;
+            for (place, mult, _) in self.parse_place_list()? {
+                if mult > 0 {
+                    let place = NodeRef::Place(place);
+                    self.net.add_weighted_arc(transition, place, mult)?;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// `p : n, p, ..;` possibly empty, repeated places get accumulated
+    fn parse_place_list(&mut self) -> Result<Vec<PlaceCount>, PetriError> {
+        let mut list: Vec<PlaceCount> = Vec::new();
+        if self.token == Token::Semicolon {
+            self.advance()?;
+            return Ok(list);
+        }
+        loop {
+            let position = (self.token_line, self.token_column);
+            let place = self.place()?;
+            let count = if self.token == Token::Colon {
+                self.advance()?;
+                self.number()?
+            } else {
+                1
+            };
+            match list.iter_mut().find(|(p, _, _)| *p == place) {
+                Some((_, c, last)) => {
+                    *c += count;
+                    *last = position;
+                }
+                None => list.push((place, count, position)),
+            }
+            if self.token != Token::Comma {
+                break;
+            }
+            self.advance()?;
+        }
+        self.expect(Token::Semicolon)?;
+        Ok(list)
+    }
 }
-fn make_id<'a>(prefix: &str, id: usize) -> String {
-    let mut ret = String::from(prefix);
-    ret.push_str(&id.to_string());
-    ret
-}
+
+/// place with its accumulated count and the position of its last occurrence
+type PlaceCount = (PlaceRef, usize, (usize, usize));
+
+const KEYWORDS: [&str; 9] = [
+    "PLACE",
+    "MARKING",
+    "TRANSITION",
+    "CONSUME",
+    "PRODUCE",
+    "SAFE",
+    "STRONG",
+    "WEAK",
+    "FAIR",
+];
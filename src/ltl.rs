@@ -0,0 +1,518 @@
+use crate::{
+    Atom, ExplorationLimits, Lasso, Marking, PetriError, PetriNet, Result, TransitionRef, Verdict,
+    Witness,
+};
+use std::collections::{BTreeSet, HashMap, HashSet};
+
+/// Formula of linear temporal logic over the runs of a net. Every position
+/// of a run has a marking and the transition that fires next. Runs that reach
+/// a dead marking stay there forever without firing.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Ltl {
+    True,
+    False,
+    /// proposition about the marking of the position
+    Atom(Atom),
+    /// the transition fires at the position
+    Fired(TransitionRef),
+    Not(Box<Ltl>),
+    And(Vec<Ltl>),
+    Or(Vec<Ltl>),
+    Next(Box<Ltl>),
+    Until(Box<Ltl>, Box<Ltl>),
+    /// the second formula holds until and including the position where the
+    /// first one holds, or forever
+    Release(Box<Ltl>, Box<Ltl>),
+    Finally(Box<Ltl>),
+    Globally(Box<Ltl>),
+}
+
+impl PetriNet {
+    /// Checks that all runs from the initial marking satisfy the formula. The
+    /// negation of the formula is translated into a Büchi automaton and the
+    /// product with the reachable markings is searched on the fly for an
+    /// accepting cycle, which is returned as a lasso if the formula does not
+    /// hold. The search order of the limits is ignored.
+    /// Runs that end in a dead marking stutter in it forever, their lasso
+    /// has an empty cycle.
+    /// Fails with `LimitsExceeded` if the limits are reached before the
+    /// search is finished.
+    pub fn check_ltl(&self, formula: &Ltl, limits: &ExplorationLimits) -> Result<Verdict> {
+        let mut formulas = Formulas::default();
+        let negation = formulas.normalize(self, formula, true)?;
+        let automaton = Automaton::new(&formulas, negation);
+        let mut product = Product {
+            net: self,
+            automaton: &automaton,
+            limits,
+            marking_size: std::mem::size_of::<Marking>()
+                + self.places.len() * std::mem::size_of::<usize>(),
+            markings: Vec::new(),
+            known: HashMap::new(),
+            steps: Vec::new(),
+            visited: HashSet::new(),
+        };
+        let initial = product.intern(self.initial_marking())?;
+        let lasso = product.accepting_cycle(initial)?;
+        Ok(Verdict {
+            holds: lasso.is_none(),
+            witness: lasso.map(Witness::Lasso),
+        })
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+enum Proposition {
+    Marking(Atom),
+    Fired(TransitionRef),
+}
+
+/// Formula in negation normal form, subformulas are indices into `Formulas`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+enum Formula {
+    True,
+    False,
+    Literal(bool, Proposition),
+    And(usize, usize),
+    Or(usize, usize),
+    Next(usize),
+    Until(usize, usize),
+    Release(usize, usize),
+}
+
+/// Subformulas that are shared between all occurrences.
+#[derive(Default)]
+struct Formulas {
+    formulas: Vec<Formula>,
+    indices: HashMap<Formula, usize>,
+}
+
+impl Formulas {
+    fn add(&mut self, formula: Formula) -> usize {
+        if let Some(index) = self.indices.get(&formula) {
+            return *index;
+        }
+        self.formulas.push(formula);
+        self.indices.insert(formula, self.formulas.len() - 1);
+        self.formulas.len() - 1
+    }
+
+    /// pushes negations down to the propositions
+    fn normalize(&mut self, net: &PetriNet, formula: &Ltl, negated: bool) -> Result<usize> {
+        Ok(match formula {
+            Ltl::True => self.add(if negated {
+                Formula::False
+            } else {
+                Formula::True
+            }),
+            Ltl::False => self.add(if negated {
+                Formula::True
+            } else {
+                Formula::False
+            }),
+            Ltl::Atom(atom) => self.add(Formula::Literal(!negated, Proposition::Marking(*atom))),
+            Ltl::Fired(transition) => {
+                net.transition(*transition)?;
+                self.add(Formula::Literal(!negated, Proposition::Fired(*transition)))
+            }
+            Ltl::Not(formula) => self.normalize(net, formula, !negated)?,
+            Ltl::And(formulas) | Ltl::Or(formulas) => {
+                let conjunction = matches!(formula, Ltl::And(_)) != negated;
+                let mut result = self.add(if conjunction {
+                    Formula::True
+                } else {
+                    Formula::False
+                });
+                for (position, formula) in formulas.iter().enumerate() {
+                    let operand = self.normalize(net, formula, negated)?;
+                    result = if position == 0 {
+                        operand
+                    } else if conjunction {
+                        self.add(Formula::And(result, operand))
+                    } else {
+                        self.add(Formula::Or(result, operand))
+                    };
+                }
+                result
+            }
+            Ltl::Next(formula) => {
+                let operand = self.normalize(net, formula, negated)?;
+                self.add(Formula::Next(operand))
+            }
+            Ltl::Until(hold, goal) | Ltl::Release(hold, goal) => {
+                let hold = self.normalize(net, hold, negated)?;
+                let goal = self.normalize(net, goal, negated)?;
+                if matches!(formula, Ltl::Until(..)) != negated {
+                    self.add(Formula::Until(hold, goal))
+                } else {
+                    self.add(Formula::Release(hold, goal))
+                }
+            }
+            Ltl::Finally(goal) => {
+                let goal = self.normalize(net, goal, negated)?;
+                if negated {
+                    let never = self.add(Formula::False);
+                    self.add(Formula::Release(never, goal))
+                } else {
+                    let always = self.add(Formula::True);
+                    self.add(Formula::Until(always, goal))
+                }
+            }
+            Ltl::Globally(hold) => {
+                let hold = self.normalize(net, hold, negated)?;
+                if negated {
+                    let always = self.add(Formula::True);
+                    self.add(Formula::Until(always, hold))
+                } else {
+                    let never = self.add(Formula::False);
+                    self.add(Formula::Release(never, hold))
+                }
+            }
+        })
+    }
+}
+
+/// incoming edge of the automaton states that are initial
+const INITIAL: usize = usize::MAX;
+
+/// Node of the tableau construction by Gerth, Peled, Vardi and Wolper.
+#[derive(Clone)]
+struct Node {
+    incoming: BTreeSet<usize>,
+    new: BTreeSet<usize>,
+    old: BTreeSet<usize>,
+    next: BTreeSet<usize>,
+}
+
+/// Generalized Büchi automaton, every state constrains the position of the
+/// run that it stands for.
+struct Automaton {
+    /// literals that have to hold at the position
+    literals: Vec<Vec<(bool, Proposition)>>,
+    successors: Vec<Vec<usize>>,
+    initial: Vec<usize>,
+    /// one set of states per until subformula, a run has to visit every set
+    /// infinitely often
+    accepting: Vec<Vec<bool>>,
+}
+
+impl Automaton {
+    fn new(formulas: &Formulas, formula: usize) -> Self {
+        let mut nodes = Vec::new();
+        expand(
+            formulas,
+            Node {
+                incoming: vec![INITIAL].into_iter().collect(),
+                new: vec![formula].into_iter().collect(),
+                old: BTreeSet::new(),
+                next: BTreeSet::new(),
+            },
+            &mut nodes,
+        );
+        let mut successors = vec![Vec::new(); nodes.len()];
+        let mut initial = Vec::new();
+        for (state, node) in nodes.iter().enumerate() {
+            for source in &node.incoming {
+                if *source == INITIAL {
+                    initial.push(state);
+                } else {
+                    successors[*source].push(state);
+                }
+            }
+        }
+        let literals = nodes
+            .iter()
+            .map(|node| {
+                node.old
+                    .iter()
+                    .filter_map(|formula| match formulas.formulas[*formula] {
+                        Formula::Literal(positive, proposition) => Some((positive, proposition)),
+                        _ => None,
+                    })
+                    .collect()
+            })
+            .collect();
+        let accepting = formulas
+            .formulas
+            .iter()
+            .enumerate()
+            .filter_map(|(index, formula)| match formula {
+                Formula::Until(_, goal) => Some(
+                    nodes
+                        .iter()
+                        .map(|node| !node.old.contains(&index) || node.old.contains(goal))
+                        .collect(),
+                ),
+                _ => None,
+            })
+            .collect();
+        Automaton {
+            literals,
+            successors,
+            initial,
+            accepting,
+        }
+    }
+
+    /// number of counter values of the degeneralized automaton
+    fn rounds(&self) -> usize {
+        self.accepting.len().max(1)
+    }
+
+    fn is_accepting(&self, state: usize, round: usize) -> bool {
+        self.accepting
+            .get(round)
+            .is_none_or(|accepting| accepting[state])
+    }
+}
+
+fn expand(formulas: &Formulas, mut node: Node, nodes: &mut Vec<Node>) {
+    let formula = match node.new.iter().next() {
+        Some(formula) => *formula,
+        None => {
+            let existing = nodes
+                .iter()
+                .position(|other| other.old == node.old && other.next == node.next);
+            match existing {
+                Some(existing) => nodes[existing].incoming.extend(node.incoming),
+                None => {
+                    let next = node.next.clone();
+                    nodes.push(node);
+                    let successor = Node {
+                        incoming: vec![nodes.len() - 1].into_iter().collect(),
+                        new: next,
+                        old: BTreeSet::new(),
+                        next: BTreeSet::new(),
+                    };
+                    expand(formulas, successor, nodes);
+                }
+            }
+            return;
+        }
+    };
+    node.new.remove(&formula);
+    if node.old.contains(&formula) {
+        return expand(formulas, node, nodes);
+    }
+    node.old.insert(formula);
+    // formulas that are required in the node itself, its split and the next position
+    let (now, split, next): (Vec<usize>, Option<Vec<usize>>, Vec<usize>) = match formulas.formulas
+        [formula]
+    {
+        Formula::True => (Vec::new(), None, Vec::new()),
+        Formula::False => return,
+        Formula::Literal(positive, proposition) => {
+            let contradiction = formulas
+                .indices
+                .get(&Formula::Literal(!positive, proposition))
+                .is_some_and(|negation| node.old.contains(negation));
+            if contradiction {
+                return;
+            }
+            (Vec::new(), None, Vec::new())
+        }
+        Formula::And(left, right) => (vec![left, right], None, Vec::new()),
+        Formula::Or(left, right) => (vec![left], Some(vec![right]), Vec::new()),
+        Formula::Next(operand) => (Vec::new(), None, vec![operand]),
+        Formula::Until(hold, goal) => (vec![hold], Some(vec![goal]), vec![formula]),
+        Formula::Release(release, hold) => (vec![hold], Some(vec![release, hold]), vec![formula]),
+    };
+    if let Some(split) = split {
+        let mut other = node.clone();
+        let split: Vec<usize> = split
+            .into_iter()
+            .filter(|f| !other.old.contains(f))
+            .collect();
+        other.new.extend(split);
+        expand(formulas, other, nodes);
+    }
+    let now: Vec<usize> = now.into_iter().filter(|f| !node.old.contains(f)).collect();
+    node.new.extend(now);
+    node.next.extend(next);
+    expand(formulas, node, nodes);
+}
+
+/// transition and successor marking, `None` for the loop of dead markings
+type Step = (Option<TransitionRef>, usize);
+
+/// (marking, automaton state, counter of the degeneralization)
+type ProductState = (usize, usize, usize);
+
+/// transition that leads to a state, `None` for the initial state and the
+/// loops of dead markings
+type ProductEdge = (Option<TransitionRef>, ProductState);
+
+struct Frame {
+    state: ProductState,
+    via: Option<TransitionRef>,
+    successors: Vec<ProductEdge>,
+    next: usize,
+}
+
+/// Product of the reachable markings and the automaton, explored on the fly.
+struct Product<'a> {
+    net: &'a PetriNet,
+    automaton: &'a Automaton,
+    limits: &'a ExplorationLimits,
+    marking_size: usize,
+    markings: Vec<Marking>,
+    known: HashMap<Marking, usize>,
+    /// steps of the markings, computed on first use
+    steps: Vec<Option<Vec<Step>>>,
+    visited: HashSet<ProductState>,
+}
+
+impl Product<'_> {
+    fn intern(&mut self, marking: Marking) -> Result<usize> {
+        if let Some(index) = self.known.get(&marking) {
+            return Ok(*index);
+        }
+        let exceeds_states = self
+            .limits
+            .max_states
+            .is_some_and(|max| self.markings.len() >= max);
+        let memory = (self.markings.len() + 1) * 2 * self.marking_size
+            + self.visited.len() * 2 * std::mem::size_of::<ProductState>();
+        let exceeds_memory = self.limits.max_memory.is_some_and(|max| memory > max);
+        if exceeds_states || exceeds_memory {
+            return Err(PetriError::LimitsExceeded);
+        }
+        self.known.insert(marking.clone(), self.markings.len());
+        self.markings.push(marking);
+        self.steps.push(None);
+        Ok(self.markings.len() - 1)
+    }
+
+    fn steps(&mut self, state: usize) -> Result<Vec<Step>> {
+        if let Some(steps) = &self.steps[state] {
+            return Ok(steps.clone());
+        }
+        let marking = self.markings[state].clone();
+        let mut steps = Vec::new();
+        for transition in self.net.enabled_transitions(&marking) {
+            let mut successor = marking.clone();
+            transition.fire(self.net, &mut successor)?;
+            steps.push((Some(transition), self.intern(successor)?));
+        }
+        if steps.is_empty() {
+            steps.push((None, state));
+        }
+        self.steps[state] = Some(steps.clone());
+        Ok(steps)
+    }
+
+    fn successors(&mut self, (marking, node, round): ProductState) -> Result<Vec<ProductEdge>> {
+        let mut successors = Vec::new();
+        for (positive, proposition) in &self.automaton.literals[node] {
+            if let Proposition::Marking(atom) = proposition {
+                if atom.holds(self.net, &self.markings[marking])? != *positive {
+                    return Ok(successors);
+                }
+            }
+        }
+        let round = if self.automaton.is_accepting(node, round) {
+            (round + 1) % self.automaton.rounds()
+        } else {
+            round
+        };
+        for (transition, target) in self.steps(marking)? {
+            let fires = self.automaton.literals[node]
+                .iter()
+                .all(|(positive, proposition)| match proposition {
+                    Proposition::Fired(fired) => (transition == Some(*fired)) == *positive,
+                    Proposition::Marking(_) => true,
+                });
+            if fires {
+                for successor in &self.automaton.successors[node] {
+                    successors.push((transition, (target, *successor, round)));
+                }
+            }
+        }
+        Ok(successors)
+    }
+
+    fn frame(&mut self, state: ProductState, via: Option<TransitionRef>) -> Result<Frame> {
+        Ok(Frame {
+            state,
+            via,
+            successors: self.successors(state)?,
+            next: 0,
+        })
+    }
+
+    /// Nested depth first search: every accepting state is the seed of a
+    /// second search for a state on the stack of the first one, which closes
+    /// a cycle through the seed.
+    fn accepting_cycle(&mut self, marking: usize) -> Result<Option<Lasso>> {
+        let mut flagged = HashSet::new();
+        for node in self.automaton.initial.clone() {
+            let initial = (marking, node, 0);
+            if !self.visited.insert(initial) {
+                continue;
+            }
+            let mut stack = vec![self.frame(initial, None)?];
+            let mut on_stack: HashMap<ProductState, usize> = HashMap::new();
+            on_stack.insert(initial, 0);
+            while let Some(frame) = stack.last_mut() {
+                if let Some((via, successor)) = frame.successors.get(frame.next).copied() {
+                    frame.next += 1;
+                    if self.visited.insert(successor) {
+                        on_stack.insert(successor, stack.len());
+                        let frame = self.frame(successor, via)?;
+                        stack.push(frame);
+                    }
+                    continue;
+                }
+                let seed = frame.state;
+                let (_, node, round) = seed;
+                if round == 0 && self.automaton.is_accepting(node, 0) {
+                    if let Some(lasso) = self.close_cycle(seed, &stack, &on_stack, &mut flagged)? {
+                        return Ok(Some(lasso));
+                    }
+                }
+                on_stack.remove(&seed);
+                stack.pop();
+            }
+        }
+        Ok(None)
+    }
+
+    fn close_cycle(
+        &mut self,
+        seed: ProductState,
+        outer: &[Frame],
+        on_stack: &HashMap<ProductState, usize>,
+        flagged: &mut HashSet<ProductState>,
+    ) -> Result<Option<Lasso>> {
+        let mut stack = vec![self.frame(seed, None)?];
+        flagged.insert(seed);
+        while let Some(frame) = stack.last_mut() {
+            let (via, successor) = match frame.successors.get(frame.next).copied() {
+                Some(edge) => edge,
+                None => {
+                    stack.pop();
+                    continue;
+                }
+            };
+            frame.next += 1;
+            if let Some(position) = on_stack.get(&successor) {
+                let prefix = outer[1..=*position]
+                    .iter()
+                    .filter_map(|frame| frame.via)
+                    .collect();
+                let cycle = outer[*position + 1..]
+                    .iter()
+                    .chain(&stack[1..])
+                    .filter_map(|frame| frame.via)
+                    .chain(via)
+                    .collect();
+                return Ok(Some(Lasso { prefix, cycle }));
+            }
+            if flagged.insert(successor) {
+                let frame = self.frame(successor, via)?;
+                stack.push(frame);
+            }
+        }
+        Ok(None)
+    }
+}
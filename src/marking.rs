@@ -0,0 +1,185 @@
+use crate::{ArcKind, ArcRef, PetriError, PetriNet, PlaceRef, Result, TransitionRef};
+use std::collections::BTreeMap;
+
+/// (place, tokens) pairs of the arcs connected to a transition
+type Weights = Vec<(PlaceRef, usize)>;
+
+/// Token distribution over the places of a net.
+/// Other than the initial marking stored in the places of the net, a marking
+/// is a plain value that changes by firing transitions. It always covers all
+/// places of the net it was created from, including the removed ones, so equal
+/// token distributions are equal markings.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Marking {
+    pub(crate) tokens: Vec<usize>,
+}
+
+impl Marking {
+    /// tokens on the given place, places that are unknown to the marking are empty
+    pub fn tokens(&self, place: PlaceRef) -> usize {
+        self.tokens.get(place.index).copied().unwrap_or(0)
+    }
+
+    /// Fails with `PlaceNotFound` if the place was added to the net after the
+    /// marking was created.
+    pub fn set_tokens(&mut self, place: PlaceRef, tokens: usize) -> Result<()> {
+        match self.tokens.get_mut(place.index) {
+            Some(current) => {
+                *current = tokens;
+                Ok(())
+            }
+            None => Err(PetriError::PlaceNotFound),
+        }
+    }
+
+    /// all places with at least one token
+    pub fn marked_places(&self) -> impl Iterator<Item = (PlaceRef, usize)> + '_ {
+        self.tokens
+            .iter()
+            .enumerate()
+            .filter(|(_, tokens)| **tokens > 0)
+            .map(|(index, tokens)| (PlaceRef { index }, *tokens))
+    }
+}
+
+impl PetriNet {
+    pub fn initial_marking(&self) -> Marking {
+        Marking {
+            tokens: self
+                .places
+                .iter()
+                .map(|place| place.as_ref().map_or(0, |place| place.marking))
+                .collect(),
+        }
+    }
+
+    /// Markings of the net before places were added do not fit anymore.
+    pub(crate) fn check_marking(&self, marking: &Marking) -> Result<()> {
+        if marking.tokens.len() == self.places.len() {
+            Ok(())
+        } else {
+            Err(PetriError::InvalidData(format!(
+                "the marking covers {} places but the net has {}",
+                marking.tokens.len(),
+                self.places.len()
+            )))
+        }
+    }
+
+    pub fn enabled_transitions<'net>(
+        &'net self,
+        marking: &'net Marking,
+    ) -> impl Iterator<Item = TransitionRef> + 'net {
+        self.transitions()
+            .filter(move |transition| transition.is_enabled(self, marking).unwrap_or(false))
+    }
+
+    /// consumed and produced tokens of a transition, summed up over all its arcs
+    pub(crate) fn weights(&self, transition: TransitionRef) -> Result<(Weights, Weights)> {
+        let node = self.transition(transition)?;
+        let weights = |set: &BTreeMap<PlaceRef, ArcRef>| -> Result<Weights> {
+            set.iter()
+                .map(|(place, arc)| Ok((*place, self.arc(*arc)?.mult)))
+                .collect()
+        };
+        Ok((weights(&node.preset)?, weights(&node.postset)?))
+    }
+
+    /// (place, multiplicity) pairs of the arcs of the given kind that lead
+    /// to a transition, for non normal arcs only
+    pub(crate) fn special_weights(
+        &self,
+        transition: TransitionRef,
+        kind: ArcKind,
+    ) -> Result<Weights> {
+        self.transition(transition)?
+            .special
+            .iter()
+            .filter(|((_, arc_kind), _)| *arc_kind == kind)
+            .map(|((place, _), arc)| Ok((*place, self.arc(*arc)?.mult)))
+            .collect()
+    }
+}
+
+impl TransitionRef {
+    /// A transition is enabled if all places in its preset hold at least as
+    /// many tokens as the connecting arcs consume, places connected by read
+    /// arcs hold at least the weight of the arc and places connected by
+    /// inhibitor arcs hold less tokens than the threshold of the arc.
+    /// Additionally no place may exceed its capacity after firing (strict
+    /// firing rule) and no transition with a higher priority may satisfy
+    /// these conditions.
+    /// Fails with `InvalidData` if the marking does not cover the places of
+    /// the net.
+    pub fn is_enabled(self, net: &PetriNet, marking: &Marking) -> Result<bool> {
+        net.check_marking(marking)?;
+        if !self.has_concession(net, marking)? {
+            return Ok(false);
+        }
+        if net.has_priorities() {
+            for higher in net.higher_priorities(self) {
+                if higher.has_concession(net, marking)? {
+                    return Ok(false);
+                }
+            }
+        }
+        Ok(true)
+    }
+
+    /// enabled if priorities are ignored
+    pub(crate) fn has_concession(self, net: &PetriNet, marking: &Marking) -> Result<bool> {
+        let (consume, produce) = net.weights(self)?;
+        let read = net.special_weights(self, ArcKind::Read)?;
+        let inhibitors = net.special_weights(self, ArcKind::Inhibitor)?;
+        if !consume
+            .iter()
+            .chain(read.iter())
+            .all(|(place, weight)| marking.tokens(*place) >= *weight)
+            || !inhibitors
+                .iter()
+                .all(|(place, threshold)| marking.tokens(*place) < *threshold)
+        {
+            return Ok(false);
+        }
+        // only places that receive tokens can exceed their capacity
+        let resets = net.special_weights(self, ArcKind::Reset)?;
+        for (place, weight) in produce {
+            if let Some(capacity) = net.place(place)?.capacity {
+                let remaining = if resets.iter().any(|(reset, _)| *reset == place) {
+                    0
+                } else {
+                    let consumed = consume
+                        .iter()
+                        .find(|(consumed, _)| *consumed == place)
+                        .map_or(0, |(_, weight)| *weight);
+                    marking.tokens(place) - consumed
+                };
+                if remaining + weight > capacity {
+                    return Ok(false);
+                }
+            }
+        }
+        Ok(true)
+    }
+
+    /// Fire the transition in the given marking. Places connected by reset
+    /// arcs are emptied after the consumed tokens are removed and before the
+    /// produced tokens are added.
+    /// The marking is left untouched if the transition is not enabled.
+    pub fn fire(self, net: &PetriNet, marking: &mut Marking) -> Result<()> {
+        if !self.is_enabled(net, marking)? {
+            return Err(PetriError::TransitionNotEnabled);
+        }
+        let (consume, produce) = net.weights(self)?;
+        for (place, weight) in consume {
+            marking.set_tokens(place, marking.tokens(place) - weight)?;
+        }
+        for (place, _) in net.special_weights(self, ArcKind::Reset)? {
+            marking.set_tokens(place, 0)?;
+        }
+        for (place, weight) in produce {
+            marking.set_tokens(place, marking.tokens(place) + weight)?;
+        }
+        Ok(())
+    }
+}
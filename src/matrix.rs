@@ -0,0 +1,177 @@
+use crate::{PetriNet, PlaceRef, Result, TransitionRef};
+use std::collections::BTreeMap;
+
+/// Matrix with a row per place and a column per transition.
+/// Only entries that are not zero are stored.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SparseMatrix {
+    places: Vec<PlaceRef>,
+    transitions: Vec<TransitionRef>,
+    entries: BTreeMap<(PlaceRef, TransitionRef), i64>,
+}
+
+impl SparseMatrix {
+    fn new(net: &PetriNet) -> Self {
+        SparseMatrix {
+            places: net.places().collect(),
+            transitions: net.transitions().collect(),
+            entries: BTreeMap::new(),
+        }
+    }
+
+    fn add(&mut self, place: PlaceRef, transition: TransitionRef, value: i64) {
+        let entry = self.entries.entry((place, transition)).or_insert(0);
+        *entry += value;
+        if *entry == 0 {
+            self.entries.remove(&(place, transition));
+        }
+    }
+
+    pub fn get(&self, place: PlaceRef, transition: TransitionRef) -> i64 {
+        self.entries.get(&(place, transition)).copied().unwrap_or(0)
+    }
+
+    /// row order of the exports
+    pub fn places(&self) -> &[PlaceRef] {
+        &self.places
+    }
+
+    /// column order of the exports
+    pub fn transitions(&self) -> &[TransitionRef] {
+        &self.transitions
+    }
+
+    /// non-zero entries ordered by place and transition
+    pub fn entries(&self) -> impl Iterator<Item = (PlaceRef, TransitionRef, i64)> + '_ {
+        self.entries
+            .iter()
+            .map(|((place, transition), value)| (*place, *transition, *value))
+    }
+
+    pub fn to_matrix_market_string(&self) -> std::result::Result<String, std::io::Error> {
+        let mut writer = Vec::new();
+        self.to_matrix_market(&mut writer)?;
+        Ok(String::from_utf8(writer).expect("Document generated non UTF-8 string"))
+    }
+
+    /// MatrixMarket coordinate format, row `i` belongs to the `i`-th place
+    /// and column `j` to the `j`-th transition (both counted from one)
+    pub fn to_matrix_market<T>(&self, writer: &mut T) -> std::result::Result<(), std::io::Error>
+    where
+        T: std::io::Write,
+    {
+        let rows: BTreeMap<PlaceRef, usize> = self
+            .places
+            .iter()
+            .enumerate()
+            .map(|(row, place)| (*place, row))
+            .collect();
+        let columns: BTreeMap<TransitionRef, usize> = self
+            .transitions
+            .iter()
+            .enumerate()
+            .map(|(column, transition)| (*transition, column))
+            .collect();
+        let entries: Vec<(usize, usize, i64)> = self
+            .entries()
+            .map(|(place, transition, value)| (rows[&place], columns[&transition], value))
+            .collect();
+        write_matrix_market(
+            writer,
+            "integer",
+            self.places.len(),
+            self.transitions.len(),
+            &entries,
+        )
+    }
+
+    pub fn to_csv_string(&self) -> std::result::Result<String, std::io::Error> {
+        let mut writer = Vec::new();
+        self.to_csv(&mut writer)?;
+        Ok(String::from_utf8(writer).expect("Document generated non UTF-8 string"))
+    }
+
+    /// ```text
+    /// ,t_0,t_1
+    /// p_0,-1,0
+    /// p_1,1,-2
+    /// ```
+    pub fn to_csv<T>(&self, writer: &mut T) -> std::result::Result<(), std::io::Error>
+    where
+        T: std::io::Write,
+    {
+        const PLACE_PREFIX: &str = "p_";
+        const TRANSITION_PREFIX: &str = "t_";
+        for transition in &self.transitions {
+            write!(writer, ",{}{}", TRANSITION_PREFIX, transition.index)?;
+        }
+        writer.write_all("\n".as_bytes())?;
+        for place in &self.places {
+            write!(writer, "{}{}", PLACE_PREFIX, place.index)?;
+            for transition in &self.transitions {
+                write!(writer, ",{}", self.get(*place, *transition))?;
+            }
+            writer.write_all("\n".as_bytes())?;
+        }
+        Ok(())
+    }
+}
+
+/// `entries` are zero based (row, column, value) triples
+pub(crate) fn write_matrix_market<T, V>(
+    writer: &mut T,
+    field: &str,
+    rows: usize,
+    columns: usize,
+    entries: &[(usize, usize, V)],
+) -> std::result::Result<(), std::io::Error>
+where
+    T: std::io::Write,
+    V: std::fmt::Display,
+{
+    writeln!(writer, "%%MatrixMarket matrix coordinate {} general", field)?;
+    writeln!(writer, "{} {} {}", rows, columns, entries.len())?;
+    for (row, column, value) in entries {
+        writeln!(writer, "{} {} {}", row + 1, column + 1, value)?;
+    }
+    Ok(())
+}
+
+impl PetriNet {
+    /// tokens consumed from a place by a transition
+    pub fn pre_matrix(&self) -> Result<SparseMatrix> {
+        let mut matrix = SparseMatrix::new(self);
+        for transition in self.transitions() {
+            for (place, weight) in self.weights(transition)?.0 {
+                matrix.add(place, transition, weight as i64);
+            }
+        }
+        Ok(matrix)
+    }
+
+    /// tokens produced on a place by a transition
+    pub fn post_matrix(&self) -> Result<SparseMatrix> {
+        let mut matrix = SparseMatrix::new(self);
+        for transition in self.transitions() {
+            for (place, weight) in self.weights(transition)?.1 {
+                matrix.add(place, transition, weight as i64);
+            }
+        }
+        Ok(matrix)
+    }
+
+    /// change of the tokens on a place when a transition fires: post - pre
+    pub fn incidence_matrix(&self) -> Result<SparseMatrix> {
+        let mut matrix = SparseMatrix::new(self);
+        for transition in self.transitions() {
+            let (consume, produce) = self.weights(transition)?;
+            for (place, weight) in consume {
+                matrix.add(place, transition, -(weight as i64));
+            }
+            for (place, weight) in produce {
+                matrix.add(place, transition, weight as i64);
+            }
+        }
+        Ok(matrix)
+    }
+}
@@ -0,0 +1,196 @@
+use crate::{NodeRef, PetriError, PetriNet, PlaceRef, Result, TransitionRef};
+use std::collections::{HashMap, HashSet};
+
+impl PetriNet {
+    /// first place with the given name
+    pub fn place_by_name(&self, name: &str) -> Option<PlaceRef> {
+        self.names.get(name)?.iter().find_map(|node| match node {
+            NodeRef::Place(place) => Some(*place),
+            NodeRef::Transition(_) => None,
+        })
+    }
+
+    /// first transition with the given name
+    pub fn transition_by_name(&self, name: &str) -> Option<TransitionRef> {
+        self.names.get(name)?.iter().find_map(|node| match node {
+            NodeRef::Place(_) => None,
+            NodeRef::Transition(transition) => Some(*transition),
+        })
+    }
+
+    /// first node with the given name, places and transitions share one namespace
+    pub fn node_by_name(&self, name: &str) -> Option<NodeRef> {
+        self.names.get(name)?.first().copied()
+    }
+
+    /// In strict mode no two nodes can have the same name.
+    /// Enabling it fails with `DuplicateName` if the net already has duplicates.
+    pub fn set_unique_names(&mut self, unique: bool) -> Result<()> {
+        if unique {
+            let mut duplicates: Vec<&String> = self
+                .names
+                .iter()
+                .filter(|(_, nodes)| nodes.len() > 1)
+                .map(|(name, _)| name)
+                .collect();
+            duplicates.sort();
+            if let Some(name) = duplicates.first() {
+                return Err(PetriError::DuplicateName((*name).clone()));
+            }
+        }
+        self.unique_names = unique;
+        Ok(())
+    }
+
+    /// Let the LoLA, PNML, DOT and TINA exporters derive node identifiers from the
+    /// node names. Names are reduced to letters, digits and underscores;
+    /// unnamed nodes and names that clash keep an index based identifier.
+    pub fn set_name_ids(&mut self, name_ids: bool) {
+        self.name_ids = name_ids;
+    }
+
+    pub(crate) fn check_name(&self, node: NodeRef, name: &str) -> Result<()> {
+        if self.unique_names {
+            if let Some(nodes) = self.names.get(name) {
+                if nodes.iter().any(|other| *other != node) {
+                    return Err(PetriError::DuplicateName(name.to_string()));
+                }
+            }
+        }
+        Ok(())
+    }
+
+    pub(crate) fn unindex_name(&mut self, node: NodeRef, name: Option<String>) {
+        if let Some(name) = name {
+            if let Some(nodes) = self.names.get_mut(&name) {
+                nodes.retain(|other| *other != node);
+                if nodes.is_empty() {
+                    self.names.remove(&name);
+                }
+            }
+        }
+    }
+
+    /// Unique identifiers of all nodes for an export.
+    /// Without name identifiers these are the prefix followed by the index.
+    pub(crate) fn node_ids(&self, place_prefix: &str, transition_prefix: &str) -> NodeIds {
+        let mut ids = NodeIds {
+            places: HashMap::new(),
+            transitions: HashMap::new(),
+        };
+        // identifiers are reserved in a fixed order to be reproducible
+        let mut used: HashSet<String> = RESERVED.iter().map(|id| id.to_string()).collect();
+        let mut reserve = |name: Option<&String>, prefix: &str, index: usize| {
+            let fallback = format!("{}{}", prefix, index);
+            let mut id = match name {
+                Some(name) if self.name_ids => sanitize(name, prefix),
+                _ => fallback.clone(),
+            };
+            if used.contains(&id) {
+                id = fallback;
+            }
+            let mut suffix = 1;
+            let base = id.clone();
+            while used.contains(&id) {
+                id = format!("{}_{}", base, suffix);
+                suffix += 1;
+            }
+            used.insert(id.clone());
+            id
+        };
+        for place in self.places() {
+            let name = self.place(place).ok().and_then(|p| p.name.as_ref());
+            let id = reserve(name, place_prefix, place.index);
+            ids.places.insert(place, id);
+        }
+        for transition in self.transitions() {
+            let name = self
+                .transition(transition)
+                .ok()
+                .and_then(|t| t.name.as_ref());
+            let id = reserve(name, transition_prefix, transition.index);
+            ids.transitions.insert(transition, id);
+        }
+        ids
+    }
+}
+
+/// keywords of the export formats and LoLA formulas that cannot be used as
+/// identifiers
+const RESERVED: [&str; 41] = [
+    "PLACE",
+    "MARKING",
+    "TRANSITION",
+    "CONSUME",
+    "PRODUCE",
+    "SAFE",
+    "STRONG",
+    "WEAK",
+    "FAIR",
+    "AND",
+    "OR",
+    "NOT",
+    "TRUE",
+    "FALSE",
+    "FIREABLE",
+    "DEADLOCK",
+    "A",
+    "E",
+    "F",
+    "G",
+    "X",
+    "U",
+    "EF",
+    "EG",
+    "EX",
+    "AF",
+    "AG",
+    "AX",
+    "INITIAL",
+    "node",
+    "edge",
+    "graph",
+    "digraph",
+    "subgraph",
+    "strict",
+    "net",
+    "pl",
+    "tr",
+    "pr",
+    "lb",
+    "nt",
+];
+
+/// letters, digits and underscores, starting with a letter or underscore
+fn sanitize(name: &str, prefix: &str) -> String {
+    let id: String = name
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+        .collect();
+    match id.chars().next() {
+        Some(c) if !c.is_ascii_digit() => id,
+        _ => format!("{}{}", prefix, id),
+    }
+}
+
+pub(crate) struct NodeIds {
+    places: HashMap<PlaceRef, String>,
+    transitions: HashMap<TransitionRef, String>,
+}
+
+impl NodeIds {
+    pub fn place(&self, place: PlaceRef) -> &str {
+        &self.places[&place]
+    }
+
+    pub fn transition(&self, transition: TransitionRef) -> &str {
+        &self.transitions[&transition]
+    }
+
+    pub fn node(&self, node: NodeRef) -> &str {
+        match node {
+            NodeRef::Place(place) => self.place(place),
+            NodeRef::Transition(transition) => self.transition(transition),
+        }
+    }
+}
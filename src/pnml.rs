@@ -1,11 +1,23 @@
 use crate::data::{Arc, Place, Transition};
-use crate::{PetriNet, Result};
-use xml;
+use crate::names::NodeIds;
+use crate::{
+    ArcExpression, ArcKind, Colour, ColouredNet, ColouredPlaceRef, ColouredTransitionRef, Guard,
+    NodeRef, PetriError, PetriNet, PlaceRef, Result, Sort, SortRef, StochasticKind, Term,
+    TimeInterval, TransitionRef, VariableRef,
+};
+use std::collections::HashMap;
+use std::convert::TryFrom;
+use xml::reader::{EventReader, XmlEvent as ReaderEvent};
 use xml::writer::{EmitterConfig, XmlEvent};
 
 const ARC_PREFIX: &str = "arc_";
 const PLACE_PREFIX: &str = "place_";
 const TRANS_PREFIX: &str = "transition_";
+const SORT_PREFIX: &str = "sort_";
+const VARIABLE_PREFIX: &str = "variable_";
+const TOOL: &str = "PetriToStar";
+const PNML_NAMESPACE: &str = "http://www.pnml.org/version-2009/grammar/pnml";
+const SYMMETRIC_NET: &str = "http://www.pnml.org/version-2009/grammar/symmetricnet";
 
 impl PetriNet {
     pub fn to_pnml_string(&self) -> Result<String> {
@@ -31,42 +43,123 @@ impl PetriNet {
         Ok(())
     }
 
+    pub fn from_pnml_string(document: &str) -> Result<Self> {
+        Self::from_pnml(document.as_bytes())
+    }
+
+    /// Reads the first net of a pnml document.
+    /// Nodes of nested pages are flattened into the resulting net and
+    /// reference nodes are resolved to the nodes they point to.
+    /// Symmetric nets are unfolded, see `ColouredNet::unfold`.
+    pub fn from_pnml<T>(reader: T) -> Result<Self>
+    where
+        T: std::io::Read,
+    {
+        let document = XmlElement::parse(reader)?;
+        let net = pnml_net(&document)?;
+        if net.attributes.get("type").map(String::as_str) == Some(SYMMETRIC_NET) {
+            return SymmetricNetReader::read(net)?.unfold().map_err(corrupted);
+        }
+        let pages: Vec<&XmlElement> = net.children_named("page").collect();
+        if pages.is_empty() {
+            return Err(PetriError::PageNotFound);
+        }
+        let mut reader = PnmlReader::default();
+        for page in pages {
+            reader.read_page(page)?;
+        }
+        reader.read_priorities(net)?;
+        reader.into_net()
+    }
+
     fn write_xml<T>(&self, writer: &mut xml::writer::EventWriter<T>) -> Result<()>
     where
         T: std::io::Write,
     {
-        writer.write(
-            XmlEvent::start_element("pnml")
-                .default_ns("http://www.pnml.org/version-2009/grammar/pnml"),
-        )?;
+        writer.write(XmlEvent::start_element("pnml").default_ns(PNML_NAMESPACE))?;
         writer.write(
             XmlEvent::start_element("net")
                 .attr("id", "net0")
                 .attr("type", "http://www.pnml.org/version-2009/grammar/ptnet"),
         )?;
         writer.write(XmlEvent::start_element("page").attr("id", "page0"))?;
-        if !self.places.is_empty() {
-            for place in 0..self.places.len() {
-                self.places.get(place).unwrap().to_xml(writer, place)?;
-            }
-        };
-        if !self.transitions.is_empty() {
-            for trans in 0..self.transitions.len() {
-                self.transitions.get(trans).unwrap().to_xml(writer, trans)?;
-            }
-        };
-        if !self.arcs.is_empty() {
-            for arc in 0..self.arcs.len() {
-                self.arcs.get(arc).unwrap().to_xml(writer, arc)?;
-            }
-        };
+        let ids = self.node_ids(PLACE_PREFIX, TRANS_PREFIX);
+        for place in self.places() {
+            self.place(place)?.to_xml(writer, ids.place(place))?;
+        }
+        for trans in self.transitions() {
+            self.transition(trans)?
+                .to_xml(writer, ids.transition(trans))?;
+        }
+        for arc in self.arcs() {
+            self.arc(arc)?.to_xml(writer, arc.index, &ids)?;
+        }
+        writer.write(XmlEvent::end_element())?;
+        self.write_priorities(writer, &ids)?;
         writer.write(XmlEvent::end_element())?;
         writer.write(XmlEvent::end_element())?;
+        Ok(())
+    }
+
+    /// ptnets have no priorities, so they are stored as tool specific data
+    /// of the net
+    /// ```xml
+    /// <toolspecific tool="PetriToStar" version="0.1.0">
+    ///   <priority higher="transition_0" lower="transition_1"/>
+    /// </toolspecific>
+    /// ```
+    fn write_priorities<T>(
+        &self,
+        writer: &mut xml::writer::EventWriter<T>,
+        ids: &NodeIds,
+    ) -> Result<()>
+    where
+        T: std::io::Write,
+    {
+        if !self.has_priorities() {
+            return Ok(());
+        }
+        start_tool_specific(writer)?;
+        for (higher, lower) in self.priorities() {
+            write_empty(
+                writer,
+                XmlEvent::start_element("priority")
+                    .attr("higher", ids.transition(higher))
+                    .attr("lower", ids.transition(lower)),
+            )?;
+        }
         writer.write(XmlEvent::end_element())?;
         Ok(())
     }
 }
 
+fn start_tool_specific<T>(writer: &mut xml::writer::EventWriter<T>) -> Result<()>
+where
+    T: std::io::Write,
+{
+    writer.write(
+        XmlEvent::start_element("toolspecific")
+            .attr("tool", TOOL)
+            .attr("version", env!("CARGO_PKG_VERSION")),
+    )?;
+    Ok(())
+}
+
+/// `<name>text</name>`
+fn write_text_element<T>(
+    writer: &mut xml::writer::EventWriter<T>,
+    name: &str,
+    text: &str,
+) -> Result<()>
+where
+    T: std::io::Write,
+{
+    writer.write(XmlEvent::start_element(name))?;
+    writer.write(XmlEvent::Characters(text))?;
+    writer.write(XmlEvent::end_element())?;
+    Ok(())
+}
+
 fn name_to_xml<T>(writer: &mut xml::writer::EventWriter<T>, name: &Option<String>) -> Result<()>
 where
     T: std::io::Write,
@@ -82,24 +175,22 @@ where
 }
 
 impl Arc {
-    fn to_xml<T>(&self, writer: &mut xml::writer::EventWriter<T>, index: usize) -> Result<()>
+    fn to_xml<T>(
+        &self,
+        writer: &mut xml::writer::EventWriter<T>,
+        index: usize,
+        ids: &NodeIds,
+    ) -> Result<()>
     where
         T: std::io::Write,
     {
-        use crate::NodeRef;
-        fn get_node_id(node: NodeRef) -> String {
-            match node {
-                NodeRef::Place(p) => format!("{}{}", PLACE_PREFIX, p.index),
-                NodeRef::Transition(t) => format!("{}{}", TRANS_PREFIX, t.index),
-            }
-        }
         let id = format!("{}{}", ARC_PREFIX, index);
-        let source = get_node_id(self.source);
-        let target = get_node_id(self.sink);
+        let source = ids.node(self.source);
+        let target = ids.node(self.sink);
         let weight = self.mult.to_string();
         let start_element = XmlEvent::start_element("arc")
-            .attr("source", &source)
-            .attr("target", &target);
+            .attr("source", source)
+            .attr("target", target);
         let start_element = start_element.attr("id", &id);
         writer.write(start_element)?;
         {
@@ -113,21 +204,35 @@ impl Arc {
                 writer.write(XmlEvent::end_element())?;
             }
             writer.write(XmlEvent::end_element())?;
+            if self.kind != ArcKind::Normal {
+                // ptnets have no arc types, so they are stored as tool specific data
+                start_tool_specific(writer)?;
+                write_text_element(writer, "arcType", arc_type_name(self.kind))?;
+                writer.write(XmlEvent::end_element())?;
+            }
         }
         writer.write(XmlEvent::end_element())?;
         Ok(())
     }
 }
 
+fn arc_type_name(kind: ArcKind) -> &'static str {
+    match kind {
+        ArcKind::Normal => "normal",
+        ArcKind::Inhibitor => "inhibitor",
+        ArcKind::Read => "read",
+        ArcKind::Reset => "reset",
+    }
+}
+
 impl Place {
-    fn to_xml<T>(&self, writer: &mut xml::writer::EventWriter<T>, index: usize) -> Result<()>
+    fn to_xml<T>(&self, writer: &mut xml::writer::EventWriter<T>, id: &str) -> Result<()>
     where
         T: std::io::Write,
     {
-        let id = format!("{}{}", PLACE_PREFIX, index);
         let marking = self.marking.to_string();
         let start_element = XmlEvent::start_element("place");
-        let start_element = start_element.attr("id", &id);
+        let start_element = start_element.attr("id", id);
         writer.write(start_element)?;
         {
             name_to_xml(writer, &self.name)?;
@@ -142,6 +247,12 @@ impl Place {
                 }
                 writer.write(XmlEvent::end_element())?;
             }
+            if let Some(capacity) = self.capacity {
+                // ptnets have no capacities, so they are stored as tool specific data
+                start_tool_specific(writer)?;
+                write_text_element(writer, "capacity", &capacity.to_string())?;
+                writer.write(XmlEvent::end_element())?;
+            }
         }
         writer.write(XmlEvent::end_element())?;
         Ok(())
@@ -149,18 +260,1301 @@ impl Place {
 }
 
 impl Transition {
-    fn to_xml<T>(&self, writer: &mut xml::writer::EventWriter<T>, index: usize) -> Result<()>
+    fn to_xml<T>(&self, writer: &mut xml::writer::EventWriter<T>, id: &str) -> Result<()>
     where
         T: std::io::Write,
     {
-        let id = format!("{}{}", TRANS_PREFIX, index);
         let start_element = XmlEvent::start_element("transition");
-        let start_element = start_element.attr("id", &id);
+        let start_element = start_element.attr("id", id);
         writer.write(start_element)?;
         {
             name_to_xml(writer, &self.name)?;
+            if self.interval.is_some() || self.stochastic.is_some() {
+                // ptnets have no time intervals, rates or weights, so they
+                // are stored as tool specific data
+                start_tool_specific(writer)?;
+                if let Some(interval) = self.interval {
+                    let earliest = interval.earliest.to_string();
+                    let latest = interval.latest.map(|latest| latest.to_string());
+                    let element = XmlEvent::start_element("interval").attr("earliest", &earliest);
+                    match &latest {
+                        Some(latest) => write_empty(writer, element.attr("latest", latest))?,
+                        None => write_empty(writer, element)?,
+                    }
+                }
+                match self.stochastic {
+                    Some(StochasticKind::Exponential { rate }) => {
+                        write_text_element(writer, "rate", &rate.to_string())?
+                    }
+                    Some(StochasticKind::Immediate { weight }) => {
+                        write_text_element(writer, "weight", &weight.to_string())?
+                    }
+                    None => {}
+                }
+                writer.write(XmlEvent::end_element())?;
+            }
+        }
+        writer.write(XmlEvent::end_element())?;
+        Ok(())
+    }
+}
+
+/// Minimal element tree of an xml document.
+/// Pnml is deeply nested and the order of its elements is not fixed (arcs can
+/// appear before the nodes they connect), so the document is read completely
+/// before the net gets built.
+struct XmlElement {
+    name: String,
+    attributes: HashMap<String, String>,
+    children: Vec<XmlElement>,
+    text: String,
+}
+
+impl XmlElement {
+    fn parse<T>(reader: T) -> Result<Self>
+    where
+        T: std::io::Read,
+    {
+        let mut stack: Vec<XmlElement> = Vec::new();
+        for event in EventReader::new(reader) {
+            match event? {
+                ReaderEvent::StartElement {
+                    name, attributes, ..
+                } => stack.push(XmlElement {
+                    name: name.local_name,
+                    attributes: attributes
+                        .into_iter()
+                        .map(|attribute| (attribute.name.local_name, attribute.value))
+                        .collect(),
+                    children: Vec::new(),
+                    text: String::new(),
+                }),
+                ReaderEvent::Characters(text) | ReaderEvent::CData(text) => {
+                    if let Some(element) = stack.last_mut() {
+                        element.text.push_str(&text);
+                    }
+                }
+                ReaderEvent::EndElement { .. } => {
+                    let element = stack.pop().ok_or_else(|| {
+                        PetriError::CorruptedData("unbalanced xml elements".into())
+                    })?;
+                    match stack.last_mut() {
+                        Some(parent) => parent.children.push(element),
+                        None => return Ok(element),
+                    }
+                }
+                _ => {}
+            }
+        }
+        Err(PetriError::CorruptedData(
+            "document has no root element".into(),
+        ))
+    }
+
+    fn child(&self, name: &str) -> Option<&XmlElement> {
+        self.children.iter().find(|element| element.name == name)
+    }
+
+    fn children_named<'a>(&'a self, name: &'a str) -> impl Iterator<Item = &'a XmlElement> {
+        self.children
+            .iter()
+            .filter(move |element| element.name == name)
+    }
+
+    fn attribute(&self, name: &str) -> Result<&str> {
+        self.attributes
+            .get(name)
+            .map(String::as_str)
+            .ok_or_else(|| {
+                PetriError::CorruptedData(format!(
+                    "element \"{}\" has no attribute \"{}\"",
+                    self.name, name
+                ))
+            })
+    }
+
+    /// content of the `text` child of the child element `name` e.g.
+    /// `<name><text>content</text></name>`
+    fn text_of(&self, name: &str) -> Option<&str> {
+        self.child(name)
+            .and_then(|element| element.child("text"))
+            .map(|text| text.text.trim())
+    }
+
+    /// term in the `structure` of the child element `name` e.g.
+    /// `<condition><structure><equality>...</equality></structure></condition>`
+    fn structure_of(&self, name: &str) -> Option<&XmlElement> {
+        self.child(name)
+            .and_then(|element| element.child("structure"))
+            .and_then(|structure| structure.children.first())
+    }
+
+    /// operands of a symmetric net operator, each wrapped in a `subterm`
+    fn subterms(&self) -> impl Iterator<Item = &XmlElement> {
+        self.children_named("subterm")
+            .filter_map(|subterm| subterm.children.first())
+    }
+
+    fn operands(&self, count: usize) -> Result<Vec<&XmlElement>> {
+        let operands: Vec<&XmlElement> = self.subterms().collect();
+        if operands.len() != count {
+            return Err(PetriError::CorruptedData(format!(
+                "\"{}\" expects {} operands but has {}",
+                self.name,
+                count,
+                operands.len()
+            )));
+        }
+        Ok(operands)
+    }
+
+    fn integer_attribute(&self, name: &str) -> Result<i64> {
+        let value = self.attribute(name)?;
+        value.parse().map_err(|_| {
+            PetriError::CorruptedData(format!(
+                "attribute \"{}\" of element \"{}\" is not an integer: \"{}\"",
+                name, self.name, value
+            ))
+        })
+    }
+
+    /// numeric content of an annotation like `initialMarking` or `inscription`
+    fn number_of(&self, name: &str) -> Result<Option<usize>> {
+        self.text_of(name)
+            .map(|text| self.parse_number(name, text))
+            .transpose()
+    }
+
+    /// child `name` of the tool specific data written by this crate
+    fn tool_data(&self, name: &str) -> Option<&XmlElement> {
+        self.children_named("toolspecific")
+            .filter(|tool| tool.attributes.get("tool").map(String::as_str) == Some(TOOL))
+            .find_map(|tool| tool.child(name))
+    }
+
+    /// numeric tool specific data like `capacity`
+    fn tool_number(&self, name: &str) -> Result<Option<usize>> {
+        self.tool_data(name)
+            .map(|data| self.parse_number(name, data.text.trim()))
+            .transpose()
+    }
+
+    /// real valued tool specific data like `rate`
+    fn tool_real(&self, name: &str) -> Result<Option<f64>> {
+        self.tool_data(name)
+            .map(|data| {
+                let text = data.text.trim();
+                text.parse().map_err(|_| {
+                    PetriError::CorruptedData(format!(
+                        "\"{}\" of element \"{}\" is not a number: \"{}\"",
+                        name,
+                        self.attributes.get("id").map_or("", String::as_str),
+                        text
+                    ))
+                })
+            })
+            .transpose()
+    }
+
+    fn parse_number(&self, name: &str, text: &str) -> Result<usize> {
+        text.parse().map_err(|_| {
+            PetriError::CorruptedData(format!(
+                "\"{}\" of element \"{}\" is not a natural number: \"{}\"",
+                name,
+                self.attributes.get("id").map_or("", String::as_str),
+                text
+            ))
+        })
+    }
+}
+
+/// Collects the nodes of all pages before the arcs get connected.
+#[derive(Default)]
+struct PnmlReader {
+    net: PetriNet,
+    nodes: HashMap<String, NodeRef>,
+    /// reference node id -> referenced node id
+    references: HashMap<String, String>,
+    arcs: Vec<PendingArc>,
+    /// (higher, lower) transition ids
+    priorities: Vec<(String, String)>,
+}
+
+/// arc element whose nodes may not have been read yet
+struct PendingArc {
+    id: String,
+    source: String,
+    target: String,
+    name: Option<String>,
+    mult: usize,
+    kind: ArcKind,
+}
+
+impl PnmlReader {
+    fn read_page(&mut self, page: &XmlElement) -> Result<()> {
+        for element in &page.children {
+            match element.name.as_str() {
+                "place" => {
+                    let place = self.net.add_place();
+                    self.register(element, place)?;
+                    let place = PlaceRef::try_from(place)?;
+                    if let Some(marking) = element.number_of("initialMarking")? {
+                        place.marking(&mut self.net, marking)?;
+                    }
+                    if let Some(capacity) = element.tool_number("capacity")? {
+                        place
+                            .capacity(&mut self.net, Some(capacity))
+                            .map_err(corrupted)?;
+                    }
+                }
+                "transition" => {
+                    let transition = self.net.add_transition();
+                    self.register(element, transition)?;
+                    if let Some(interval) = element.tool_data("interval") {
+                        let bound = |name| {
+                            interval
+                                .attributes
+                                .get(name)
+                                .map(|text| element.parse_number(name, text))
+                                .transpose()
+                        };
+                        let earliest = bound("earliest")?.unwrap_or(0);
+                        let interval =
+                            TimeInterval::new(earliest, bound("latest")?).map_err(corrupted)?;
+                        TransitionRef::try_from(transition)?
+                            .interval(&mut self.net, Some(interval))?;
+                    }
+                    let rate = element.tool_real("rate")?;
+                    let weight = element.tool_real("weight")?;
+                    let kind = match (rate, weight) {
+                        (Some(rate), None) => Some(StochasticKind::Exponential { rate }),
+                        (None, Some(weight)) => Some(StochasticKind::Immediate { weight }),
+                        (None, None) => None,
+                        (Some(_), Some(_)) => {
+                            return Err(PetriError::CorruptedData(format!(
+                                "transition \"{}\" has a rate and a weight",
+                                element.attribute("id")?
+                            )))
+                        }
+                    };
+                    if kind.is_some() {
+                        TransitionRef::try_from(transition)?
+                            .stochastic(&mut self.net, kind)
+                            .map_err(corrupted)?;
+                    }
+                }
+                "referencePlace" | "referenceTransition" => {
+                    self.references.insert(
+                        element.attribute("id")?.to_string(),
+                        element.attribute("ref")?.to_string(),
+                    );
+                }
+                "arc" => self.arcs.push(PendingArc {
+                    id: element.attribute("id")?.to_string(),
+                    source: element.attribute("source")?.to_string(),
+                    target: element.attribute("target")?.to_string(),
+                    name: element.text_of("name").map(String::from),
+                    mult: element.number_of("inscription")?.unwrap_or(1),
+                    kind: arc_kind(element)?,
+                }),
+                "page" => self.read_page(element)?,
+                _ => {}
+            }
+        }
+        Ok(())
+    }
+
+    fn read_priorities(&mut self, net: &XmlElement) -> Result<()> {
+        let tool_specific = net
+            .children_named("toolspecific")
+            .filter(|tool| tool.attributes.get("tool").map(String::as_str) == Some(TOOL));
+        for tool in tool_specific {
+            for priority in tool.children_named("priority") {
+                self.priorities.push((
+                    priority.attribute("higher")?.to_string(),
+                    priority.attribute("lower")?.to_string(),
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    fn register(&mut self, element: &XmlElement, node: NodeRef) -> Result<()> {
+        let id = element.attribute("id")?;
+        if self.nodes.insert(id.to_string(), node).is_some() {
+            return Err(PetriError::CorruptedData(format!(
+                "node id \"{}\" is not unique",
+                id
+            )));
+        }
+        if let Some(name) = element.text_of("name") {
+            node.name(&mut self.net, name.to_string())?;
+        }
+        Ok(())
+    }
+
+    fn into_net(mut self) -> Result<PetriNet> {
+        for arc in std::mem::take(&mut self.arcs) {
+            let source = resolve(&self.nodes, &self.references, &arc.source)?;
+            let target = resolve(&self.nodes, &self.references, &arc.target)?;
+            // parallel arcs are merged into one arc that keeps the first name
+            let added = self.net.add_arc_of_kind(source, target, arc.mult, arc.kind);
+            let added = match added {
+                Err(PetriError::BipartitionViolation) => Err(PetriError::CorruptedData(format!(
+                    "arc \"{}\" connects nodes of the same kind",
+                    arc.id
+                ))),
+                Err(PetriError::InvalidData(_)) if arc.mult == 0 => Err(PetriError::CorruptedData(
+                    format!("arc \"{}\" has an inscription of zero", arc.id),
+                )),
+                Err(PetriError::InvalidData(_)) if arc.kind == ArcKind::Reset && arc.mult != 1 => {
+                    Err(PetriError::CorruptedData(format!(
+                        "reset arc \"{}\" has an inscription other than one",
+                        arc.id
+                    )))
+                }
+                Err(PetriError::InvalidData(_)) => Err(PetriError::CorruptedData(format!(
+                    "{} arc \"{}\" does not lead from a place to a transition",
+                    arc_type_name(arc.kind),
+                    arc.id
+                ))),
+                result => result,
+            }?;
+            if let (Some(name), None) = (arc.name, &self.net.arc(added)?.name) {
+                added.name(&mut self.net, name)?;
+            }
+        }
+        for (higher, lower) in std::mem::take(&mut self.priorities) {
+            let transition = |id: &str| -> Result<TransitionRef> {
+                let node = resolve(&self.nodes, &self.references, id)?;
+                TransitionRef::try_from(node).map_err(|_| {
+                    PetriError::CorruptedData(format!(
+                        "priority refers to place \"{}\" instead of a transition",
+                        id
+                    ))
+                })
+            };
+            let (higher, lower) = (transition(&higher)?, transition(&lower)?);
+            self.net.add_priority(higher, lower).map_err(corrupted)?;
+        }
+        Ok(self.net)
+    }
+}
+
+/// follow reference nodes until a place or transition is found
+fn resolve<N: Copy>(
+    nodes: &HashMap<String, N>,
+    references: &HashMap<String, String>,
+    id: &str,
+) -> Result<N> {
+    let mut id = id;
+    // every step follows a distinct reference, so cycles end the loop
+    for _ in 0..=references.len() {
+        if let Some(node) = nodes.get(id) {
+            return Ok(*node);
+        }
+        match references.get(id) {
+            Some(referenced) => id = referenced,
+            None => break,
+        }
+    }
+    Err(PetriError::CorruptedData(format!(
+        "arc references unknown node \"{}\"",
+        id
+    )))
+}
+
+/// Arc types are not part of ptnets. Besides our own tool specific
+/// `arcType`, the `arctype` annotation and the `type` element written by PIPE
+/// are understood.
+fn arc_kind(arc: &XmlElement) -> Result<ArcKind> {
+    let tool_specific = arc
+        .children_named("toolspecific")
+        .filter_map(|tool| tool.child("arcType"))
+        .map(|arc_type| arc_type.text.trim())
+        .next();
+    let annotation = arc.text_of("arctype");
+    let pipe = arc
+        .child("type")
+        .and_then(|arc_type| arc_type.attributes.get("value"))
+        .map(String::as_str);
+    match tool_specific.or(annotation).or(pipe) {
+        None | Some("normal") => Ok(ArcKind::Normal),
+        Some("inhibitor") => Ok(ArcKind::Inhibitor),
+        // PIPE and TINA call read arcs test arcs
+        Some("read") | Some("test") => Ok(ArcKind::Read),
+        Some("reset") => Ok(ArcKind::Reset),
+        Some(other) => Err(PetriError::CorruptedData(format!(
+            "arc \"{}\" has the unknown type \"{}\"",
+            arc.attributes.get("id").map_or("", String::as_str),
+            other
+        ))),
+    }
+}
+
+/// first net of a pnml document
+fn pnml_net(document: &XmlElement) -> Result<&XmlElement> {
+    if document.name != "pnml" {
+        return Err(PetriError::CorruptedData(format!(
+            "expected pnml root element but found \"{}\"",
+            document.name
+        )));
+    }
+    document.child("net").ok_or(PetriError::NetNotFound)
+}
+
+/// invalid expressions of a document are corrupted data
+fn corrupted(error: PetriError) -> PetriError {
+    match error {
+        PetriError::InvalidData(message) => PetriError::CorruptedData(message),
+        error => error,
+    }
+}
+
+fn write_empty<'a, T, E>(writer: &mut xml::writer::EventWriter<T>, element: E) -> Result<()>
+where
+    T: std::io::Write,
+    E: Into<XmlEvent<'a>>,
+{
+    writer.write(element)?;
+    writer.write(XmlEvent::end_element())?;
+    Ok(())
+}
+
+/// single entry of a multiset that is written as `numberof`
+enum Tokens<'a> {
+    Term(&'a Term),
+    Colour(&'a Colour),
+    /// every colour of the sort
+    All,
+}
+
+fn flatten<'a>(
+    expression: &'a ArcExpression,
+    factor: usize,
+    tokens: &mut Vec<(usize, Tokens<'a>)>,
+) {
+    match expression {
+        ArcExpression::Term(term) => tokens.push((factor, Tokens::Term(term))),
+        ArcExpression::Scaled(count, expression) => flatten(expression, factor * count, tokens),
+        ArcExpression::All => tokens.push((factor, Tokens::All)),
+        ArcExpression::Sum(expressions) => {
+            for expression in expressions {
+                flatten(expression, factor, tokens);
+            }
+        }
+    }
+}
+
+impl ColouredNet {
+    /// Writes the net as a PNML symmetric net. Enumerations are written as
+    /// cyclic enumerations, all other constructs keep their structure.
+    pub fn to_pnml_string(&self) -> Result<String> {
+        let mut writer = Vec::new();
+        let mut xml_writer = EmitterConfig::new()
+            .perform_indent(true)
+            .create_writer(&mut writer);
+        self.write_xml(&mut xml_writer)?;
+        Ok(String::from_utf8(writer).expect("Document generated non UTF-8 string"))
+    }
+
+    pub fn to_pnml<T>(&self, writer: &mut T) -> Result<()>
+    where
+        T: std::io::Write,
+    {
+        let mut xml_writer = EmitterConfig::new()
+            .perform_indent(true)
+            .create_writer(writer);
+        self.write_xml(&mut xml_writer)
+    }
+
+    pub fn from_pnml_string(document: &str) -> Result<Self> {
+        Self::from_pnml(document.as_bytes())
+    }
+
+    /// Reads the first net of a pnml document, it has to be a symmetric net.
+    /// Declarations can be part of the net or of any page. Terms with
+    /// partitions, named operators or multiset subtraction fail with
+    /// `UnsupportedFeature`.
+    pub fn from_pnml<T>(reader: T) -> Result<Self>
+    where
+        T: std::io::Read,
+    {
+        let document = XmlElement::parse(reader)?;
+        let net = pnml_net(&document)?;
+        match net.attributes.get("type") {
+            Some(net_type) if net_type == SYMMETRIC_NET => SymmetricNetReader::read(net),
+            net_type => Err(PetriError::UnsupportedFeature(format!(
+                "coloured nets of type \"{}\"",
+                net_type.map_or("", String::as_str)
+            ))),
+        }
+    }
+
+    fn write_xml<T>(&self, writer: &mut xml::writer::EventWriter<T>) -> Result<()>
+    where
+        T: std::io::Write,
+    {
+        writer.write(XmlEvent::start_element("pnml").default_ns(PNML_NAMESPACE))?;
+        writer.write(
+            XmlEvent::start_element("net")
+                .attr("id", "net0")
+                .attr("type", SYMMETRIC_NET),
+        )?;
+        self.write_declarations(writer)?;
+        writer.write(XmlEvent::start_element("page").attr("id", "page0"))?;
+        for (index, place) in self.places.iter().enumerate() {
+            let id = format!("{}{}", PLACE_PREFIX, index);
+            writer.write(XmlEvent::start_element("place").attr("id", &id))?;
+            name_to_xml(writer, &Some(place.name.clone()))?;
+            writer.write(XmlEvent::start_element("type"))?;
+            writer.write(XmlEvent::start_element("structure"))?;
+            write_usersort(writer, place.sort)?;
+            writer.write(XmlEvent::end_element())?;
+            writer.write(XmlEvent::end_element())?;
+            if !place.marking.is_empty() {
+                writer.write(XmlEvent::start_element("hlinitialMarking"))?;
+                writer.write(XmlEvent::start_element("structure"))?;
+                self.write_marking(writer, &place.marking, place.sort)?;
+                writer.write(XmlEvent::end_element())?;
+                writer.write(XmlEvent::end_element())?;
+            }
+            writer.write(XmlEvent::end_element())?;
+        }
+        for (index, transition) in self.transitions.iter().enumerate() {
+            let id = format!("{}{}", TRANS_PREFIX, index);
+            writer.write(XmlEvent::start_element("transition").attr("id", &id))?;
+            name_to_xml(writer, &Some(transition.name.clone()))?;
+            if transition.guard != Guard::True {
+                writer.write(XmlEvent::start_element("condition"))?;
+                writer.write(XmlEvent::start_element("structure"))?;
+                self.write_guard(writer, &transition.guard)?;
+                writer.write(XmlEvent::end_element())?;
+                writer.write(XmlEvent::end_element())?;
+            }
+            writer.write(XmlEvent::end_element())?;
+        }
+        for (index, arc) in self.arcs.iter().enumerate() {
+            let id = format!("{}{}", ARC_PREFIX, index);
+            let place = format!("{}{}", PLACE_PREFIX, arc.place.index);
+            let transition = format!("{}{}", TRANS_PREFIX, arc.transition.index);
+            let (source, target) = if arc.produce {
+                (transition, place)
+            } else {
+                (place, transition)
+            };
+            writer.write(
+                XmlEvent::start_element("arc")
+                    .attr("id", &id)
+                    .attr("source", &source)
+                    .attr("target", &target),
+            )?;
+            writer.write(XmlEvent::start_element("hlinscription"))?;
+            writer.write(XmlEvent::start_element("structure"))?;
+            let mut tokens = Vec::new();
+            flatten(&arc.expression, 1, &mut tokens);
+            self.write_multiset(writer, &tokens, self.place(arc.place)?.sort)?;
+            writer.write(XmlEvent::end_element())?;
+            writer.write(XmlEvent::end_element())?;
+            writer.write(XmlEvent::end_element())?;
+        }
+        writer.write(XmlEvent::end_element())?;
+        writer.write(XmlEvent::end_element())?;
+        writer.write(XmlEvent::end_element())?;
+        Ok(())
+    }
+
+    /// ```xml
+    /// <namedsort id="sort_0" name="philosopher">
+    ///   <cyclicenumeration>
+    ///     <feconstant id="sort_0_0" name="plato"/>
+    ///   </cyclicenumeration>
+    /// </namedsort>
+    /// <variabledecl id="variable_0" name="x">
+    ///   <usersort declaration="sort_0"/>
+    /// </variabledecl>
+    /// ```
+    fn write_declarations<T>(&self, writer: &mut xml::writer::EventWriter<T>) -> Result<()>
+    where
+        T: std::io::Write,
+    {
+        writer.write(XmlEvent::start_element("declaration"))?;
+        writer.write(XmlEvent::start_element("structure"))?;
+        writer.write(XmlEvent::start_element("declarations"))?;
+        for (index, (name, sort)) in self.sorts.iter().enumerate() {
+            let id = format!("{}{}", SORT_PREFIX, index);
+            writer.write(
+                XmlEvent::start_element("namedsort")
+                    .attr("id", &id)
+                    .attr("name", name),
+            )?;
+            match sort {
+                Sort::Dot => write_empty(writer, XmlEvent::start_element("dot"))?,
+                Sort::Enumeration(constants) => {
+                    // only cyclic enumerations have successors
+                    writer.write(XmlEvent::start_element("cyclicenumeration"))?;
+                    for (position, constant) in constants.iter().enumerate() {
+                        let constant_id = format!("{}_{}", id, position);
+                        write_empty(
+                            writer,
+                            XmlEvent::start_element("feconstant")
+                                .attr("id", &constant_id)
+                                .attr("name", constant),
+                        )?;
+                    }
+                    writer.write(XmlEvent::end_element())?;
+                }
+                Sort::Range { start, end } => write_range(writer, *start, *end)?,
+                Sort::Product(components) => {
+                    writer.write(XmlEvent::start_element("productsort"))?;
+                    for component in components {
+                        write_usersort(writer, *component)?;
+                    }
+                    writer.write(XmlEvent::end_element())?;
+                }
+            }
+            writer.write(XmlEvent::end_element())?;
+        }
+        for (index, (name, sort)) in self.variables.iter().enumerate() {
+            let id = format!("{}{}", VARIABLE_PREFIX, index);
+            writer.write(
+                XmlEvent::start_element("variabledecl")
+                    .attr("id", &id)
+                    .attr("name", name),
+            )?;
+            write_usersort(writer, *sort)?;
+            writer.write(XmlEvent::end_element())?;
+        }
+        writer.write(XmlEvent::end_element())?;
+        writer.write(XmlEvent::end_element())?;
+        writer.write(XmlEvent::end_element())?;
+        Ok(())
+    }
+
+    /// every colour with the same count is written as `all`
+    fn write_marking<T>(
+        &self,
+        writer: &mut xml::writer::EventWriter<T>,
+        marking: &std::collections::BTreeMap<Colour, usize>,
+        sort: SortRef,
+    ) -> Result<()>
+    where
+        T: std::io::Write,
+    {
+        let counts: Vec<usize> = marking.values().copied().collect();
+        if marking.len() > 1
+            && marking.len() == self.colours(sort)?.len()
+            && counts.iter().all(|count| *count == counts[0])
+        {
+            return self.write_multiset(writer, &[(counts[0], Tokens::All)], sort);
+        }
+        let tokens: Vec<(usize, Tokens)> = marking
+            .iter()
+            .map(|(colour, count)| (*count, Tokens::Colour(colour)))
+            .collect();
+        self.write_multiset(writer, &tokens, sort)
+    }
+
+    /// ```xml
+    /// <numberof>
+    ///   <subterm><numberconstant value="2"><positive/></numberconstant></subterm>
+    ///   <subterm><variable refvariable="variable_0"/></subterm>
+    /// </numberof>
+    /// ```
+    /// several entries are the subterms of an `add`
+    fn write_multiset<T>(
+        &self,
+        writer: &mut xml::writer::EventWriter<T>,
+        tokens: &[(usize, Tokens)],
+        sort: SortRef,
+    ) -> Result<()>
+    where
+        T: std::io::Write,
+    {
+        if tokens.len() != 1 {
+            writer.write(XmlEvent::start_element("add"))?;
+        }
+        for (count, entry) in tokens {
+            if tokens.len() != 1 {
+                writer.write(XmlEvent::start_element("subterm"))?;
+            }
+            writer.write(XmlEvent::start_element("numberof"))?;
+            writer.write(XmlEvent::start_element("subterm"))?;
+            writer.write(
+                XmlEvent::start_element("numberconstant").attr("value", &count.to_string()),
+            )?;
+            write_empty(writer, XmlEvent::start_element("positive"))?;
+            writer.write(XmlEvent::end_element())?;
+            writer.write(XmlEvent::end_element())?;
+            writer.write(XmlEvent::start_element("subterm"))?;
+            match entry {
+                Tokens::Term(term) => self.write_term(writer, term, Some(sort))?,
+                Tokens::Colour(colour) => self.write_colour(writer, colour, sort)?,
+                Tokens::All => {
+                    writer.write(XmlEvent::start_element("all"))?;
+                    write_usersort(writer, sort)?;
+                    writer.write(XmlEvent::end_element())?;
+                }
+            }
+            writer.write(XmlEvent::end_element())?;
+            writer.write(XmlEvent::end_element())?;
+            if tokens.len() != 1 {
+                writer.write(XmlEvent::end_element())?;
+            }
+        }
+        if tokens.len() != 1 {
+            writer.write(XmlEvent::end_element())?;
+        }
+        Ok(())
+    }
+
+    /// Constants are written for the sort of the surrounding term, or for the
+    /// first sort that contains them.
+    fn write_term<T>(
+        &self,
+        writer: &mut xml::writer::EventWriter<T>,
+        term: &Term,
+        sort: Option<SortRef>,
+    ) -> Result<()>
+    where
+        T: std::io::Write,
+    {
+        match term {
+            Term::Variable(variable) => {
+                let id = format!("{}{}", VARIABLE_PREFIX, variable.index);
+                write_empty(
+                    writer,
+                    XmlEvent::start_element("variable").attr("refvariable", &id),
+                )?;
+            }
+            Term::Constant(colour) => {
+                let sort = match sort {
+                    Some(sort) => sort,
+                    None => self.sort_containing(colour)?,
+                };
+                self.write_colour(writer, colour, sort)?;
+            }
+            Term::Successor(inner) | Term::Predecessor(inner) => {
+                let name = match term {
+                    Term::Successor(_) => "successor",
+                    _ => "predecessor",
+                };
+                writer.write(XmlEvent::start_element(name))?;
+                writer.write(XmlEvent::start_element("subterm"))?;
+                let sort = self.term_sort(inner)?.or(sort);
+                self.write_term(writer, inner, sort)?;
+                writer.write(XmlEvent::end_element())?;
+                writer.write(XmlEvent::end_element())?;
+            }
+            Term::Tuple(terms) => {
+                let sorts = match sort.map(|sort| self.sort(sort)).transpose()? {
+                    Some(Sort::Product(sorts)) if sorts.len() == terms.len() => {
+                        sorts.iter().copied().map(Some).collect()
+                    }
+                    _ => vec![None; terms.len()],
+                };
+                writer.write(XmlEvent::start_element("tuple"))?;
+                for (term, sort) in terms.iter().zip(sorts) {
+                    writer.write(XmlEvent::start_element("subterm"))?;
+                    self.write_term(writer, term, sort)?;
+                    writer.write(XmlEvent::end_element())?;
+                }
+                writer.write(XmlEvent::end_element())?;
+            }
+        }
+        Ok(())
+    }
+
+    fn write_colour<T>(
+        &self,
+        writer: &mut xml::writer::EventWriter<T>,
+        colour: &Colour,
+        sort: SortRef,
+    ) -> Result<()>
+    where
+        T: std::io::Write,
+    {
+        let missing = || {
+            PetriError::InvalidData(format!(
+                "colour {} is not part of sort {}",
+                colour, self.sorts[sort.index].0
+            ))
+        };
+        match (colour, self.sort(sort)?) {
+            (Colour::Dot, Sort::Dot) => {
+                write_empty(writer, XmlEvent::start_element("dotconstant"))?
+            }
+            (Colour::Constant(constant), Sort::Enumeration(constants)) => {
+                let position = constants
+                    .iter()
+                    .position(|other| other == constant)
+                    .ok_or_else(missing)?;
+                let id = format!("{}{}_{}", SORT_PREFIX, sort.index, position);
+                write_empty(
+                    writer,
+                    XmlEvent::start_element("useroperator").attr("declaration", &id),
+                )?;
+            }
+            (Colour::Integer(integer), Sort::Range { start, end }) => {
+                writer.write(
+                    XmlEvent::start_element("finiteintrangeconstant")
+                        .attr("value", &integer.to_string()),
+                )?;
+                write_range(writer, *start, *end)?;
+                writer.write(XmlEvent::end_element())?;
+            }
+            (Colour::Tuple(colours), Sort::Product(sorts)) if colours.len() == sorts.len() => {
+                writer.write(XmlEvent::start_element("tuple"))?;
+                for (colour, sort) in colours.iter().zip(sorts) {
+                    writer.write(XmlEvent::start_element("subterm"))?;
+                    self.write_colour(writer, colour, *sort)?;
+                    writer.write(XmlEvent::end_element())?;
+                }
+                writer.write(XmlEvent::end_element())?;
+            }
+            _ => return Err(missing()),
+        }
+        Ok(())
+    }
+
+    fn sort_containing(&self, colour: &Colour) -> Result<SortRef> {
+        self.sorts
+            .iter()
+            .position(|(_, sort)| self.position(sort, colour).is_some())
+            .map(|index| SortRef { index })
+            .ok_or_else(|| {
+                PetriError::InvalidData(format!("colour {} is not part of any sort", colour))
+            })
+    }
+
+    /// `true` and `false` are written as boolean constants
+    fn write_guard<T>(&self, writer: &mut xml::writer::EventWriter<T>, guard: &Guard) -> Result<()>
+    where
+        T: std::io::Write,
+    {
+        let (name, operands) = match guard {
+            Guard::True => ("true", None),
+            Guard::And(guards) if guards.is_empty() => ("true", None),
+            Guard::Or(guards) if guards.is_empty() => ("false", None),
+            Guard::And(guards) | Guard::Or(guards) if guards.len() == 1 => {
+                return self.write_guard(writer, &guards[0]);
+            }
+            Guard::And(_) => ("and", None),
+            Guard::Or(_) => ("or", None),
+            Guard::Not(_) => ("not", None),
+            Guard::Equal(a, b) => ("equality", Some((a, b))),
+            Guard::NotEqual(a, b) => ("inequality", Some((a, b))),
+            Guard::LessThan(a, b) => ("lessthan", Some((a, b))),
+            Guard::LessThanOrEqual(a, b) => ("lessthanorequal", Some((a, b))),
+            Guard::GreaterThan(a, b) => ("greaterthan", Some((a, b))),
+            Guard::GreaterThanOrEqual(a, b) => ("greaterthanorequal", Some((a, b))),
+        };
+        if name == "true" || name == "false" {
+            return write_empty(
+                writer,
+                XmlEvent::start_element("booleanconstant").attr("value", name),
+            );
+        }
+        writer.write(XmlEvent::start_element(name))?;
+        match (guard, operands) {
+            (_, Some((a, b))) => {
+                let sort = self.term_sort(a)?.or(self.term_sort(b)?);
+                for term in &[a, b] {
+                    writer.write(XmlEvent::start_element("subterm"))?;
+                    self.write_term(writer, term, sort)?;
+                    writer.write(XmlEvent::end_element())?;
+                }
+            }
+            (Guard::And(guards), _) | (Guard::Or(guards), _) => {
+                for guard in guards {
+                    writer.write(XmlEvent::start_element("subterm"))?;
+                    self.write_guard(writer, guard)?;
+                    writer.write(XmlEvent::end_element())?;
+                }
+            }
+            (Guard::Not(guard), _) => {
+                writer.write(XmlEvent::start_element("subterm"))?;
+                self.write_guard(writer, guard)?;
+                writer.write(XmlEvent::end_element())?;
+            }
+            _ => {}
         }
         writer.write(XmlEvent::end_element())?;
         Ok(())
     }
 }
+
+fn write_usersort<T>(writer: &mut xml::writer::EventWriter<T>, sort: SortRef) -> Result<()>
+where
+    T: std::io::Write,
+{
+    let id = format!("{}{}", SORT_PREFIX, sort.index);
+    write_empty(
+        writer,
+        XmlEvent::start_element("usersort").attr("declaration", &id),
+    )
+}
+
+fn write_range<T>(writer: &mut xml::writer::EventWriter<T>, start: i64, end: i64) -> Result<()>
+where
+    T: std::io::Write,
+{
+    write_empty(
+        writer,
+        XmlEvent::start_element("finiteintrange")
+            .attr("start", &start.to_string())
+            .attr("end", &end.to_string()),
+    )
+}
+
+#[derive(Clone, Copy)]
+enum ColouredNode {
+    Place(ColouredPlaceRef),
+    Transition(ColouredTransitionRef),
+}
+
+/// Builds a coloured net from the declarations and pages of a symmetric net.
+#[derive(Default)]
+struct SymmetricNetReader<'a> {
+    net: ColouredNet,
+    /// named sorts that have not been added to the net yet
+    sort_elements: HashMap<String, &'a XmlElement>,
+    sorts: HashMap<String, SortRef>,
+    /// feconstant id -> colour
+    constants: HashMap<String, Colour>,
+    variables: HashMap<String, VariableRef>,
+    nodes: HashMap<String, ColouredNode>,
+    /// reference node id -> referenced node id
+    references: HashMap<String, String>,
+    arcs: Vec<&'a XmlElement>,
+}
+
+/// `declarations` elements of the net and all of its pages
+fn declarations<'a>(element: &'a XmlElement, found: &mut Vec<&'a XmlElement>) {
+    for child in &element.children {
+        match child.name.as_str() {
+            "declaration" => found.extend(
+                child
+                    .child("structure")
+                    .and_then(|structure| structure.child("declarations")),
+            ),
+            "page" => declarations(child, found),
+            _ => {}
+        }
+    }
+}
+
+impl<'a> SymmetricNetReader<'a> {
+    fn read(net: &'a XmlElement) -> Result<ColouredNet> {
+        let pages: Vec<&XmlElement> = net.children_named("page").collect();
+        if pages.is_empty() {
+            return Err(PetriError::PageNotFound);
+        }
+        let mut found = Vec::new();
+        declarations(net, &mut found);
+        let mut reader = Self::default();
+        let mut sort_ids = Vec::new();
+        for element in found.iter().flat_map(|found| &found.children) {
+            if element.name == "namedsort" {
+                let id = element.attribute("id")?.to_string();
+                sort_ids.push(id.clone());
+                reader.sort_elements.insert(id, element);
+            }
+        }
+        // sorts keep the order of the document, products may refer to later ones
+        for id in sort_ids {
+            reader.named_sort(&id)?;
+        }
+        for element in found.iter().flat_map(|found| &found.children) {
+            if element.name == "variabledecl" {
+                let id = element.attribute("id")?;
+                let name = element.attributes.get("name").map_or(id, String::as_str);
+                let sort = element.children.first().ok_or_else(|| {
+                    PetriError::CorruptedData(format!("variable \"{}\" has no sort", id))
+                })?;
+                let sort = reader.sort(sort, None)?;
+                let variable = reader.net.add_variable(name.to_string(), sort)?;
+                reader.variables.insert(id.to_string(), variable);
+            }
+        }
+        for page in pages {
+            reader.read_page(page)?;
+        }
+        reader.connect()?;
+        Ok(reader.net)
+    }
+
+    fn named_sort(&mut self, id: &str) -> Result<SortRef> {
+        if let Some(sort) = self.sorts.get(id) {
+            return Ok(*sort);
+        }
+        // removed while it is read, so sorts that contain themselves are unknown
+        let element = self.sort_elements.remove(id).ok_or_else(|| {
+            PetriError::CorruptedData(format!("reference to unknown sort \"{}\"", id))
+        })?;
+        let definition = element.children.first().ok_or_else(|| {
+            PetriError::CorruptedData(format!("sort \"{}\" has no definition", id))
+        })?;
+        let name = element.attributes.get("name").map_or(id, String::as_str);
+        let sort = self.sort(definition, Some(name))?;
+        self.sorts.insert(id.to_string(), sort);
+        Ok(sort)
+    }
+
+    /// Sorts without a name reuse an equal sort of the net.
+    fn sort(&mut self, definition: &'a XmlElement, name: Option<&str>) -> Result<SortRef> {
+        let sort = match definition.name.as_str() {
+            "usersort" => return self.named_sort(definition.attribute("declaration")?),
+            "dot" => Sort::Dot,
+            "cyclicenumeration" | "finiteenumeration" => {
+                let mut constants = Vec::new();
+                for constant in definition.children_named("feconstant") {
+                    let id = constant.attribute("id")?;
+                    let name = constant.attributes.get("name").map_or(id, String::as_str);
+                    if constants.iter().any(|other| other == name) {
+                        return Err(PetriError::CorruptedData(format!(
+                            "enumeration has two constants named \"{}\"",
+                            name
+                        )));
+                    }
+                    self.constants
+                        .insert(id.to_string(), Colour::Constant(name.to_string()));
+                    constants.push(name.to_string());
+                }
+                Sort::Enumeration(constants)
+            }
+            "finiteintrange" => Sort::Range {
+                start: definition.integer_attribute("start")?,
+                end: definition.integer_attribute("end")?,
+            },
+            "productsort" => {
+                let mut components = Vec::new();
+                for component in &definition.children {
+                    components.push(self.sort(component, None)?);
+                }
+                Sort::Product(components)
+            }
+            other => {
+                return Err(PetriError::UnsupportedFeature(format!(
+                    "symmetric net sort \"{}\"",
+                    other
+                )))
+            }
+        };
+        if name.is_none() {
+            if let Some(index) = self.net.sorts.iter().position(|(_, other)| *other == sort) {
+                return Ok(SortRef { index });
+            }
+        }
+        let name = name.unwrap_or(&definition.name).to_string();
+        self.net.add_sort(name, sort).map_err(corrupted)
+    }
+
+    fn read_page(&mut self, page: &'a XmlElement) -> Result<()> {
+        for element in &page.children {
+            match element.name.as_str() {
+                "place" => {
+                    let id = element.attribute("id")?;
+                    let sort = element.structure_of("type").ok_or_else(|| {
+                        PetriError::CorruptedData(format!("place \"{}\" has no type", id))
+                    })?;
+                    let sort = self.sort(sort, None)?;
+                    let name = element.text_of("name").unwrap_or(id).to_string();
+                    let place = self.net.add_place(name, sort)?;
+                    if let Some(marking) = element.structure_of("hlinitialMarking") {
+                        let marking = self.multiset(marking)?;
+                        let marking = self
+                            .net
+                            .evaluate_marking(&marking, sort)
+                            .map_err(corrupted)?;
+                        place.marking(&mut self.net, marking)?;
+                    }
+                    self.register(id, ColouredNode::Place(place))?;
+                }
+                "transition" => {
+                    let id = element.attribute("id")?;
+                    let name = element.text_of("name").unwrap_or(id).to_string();
+                    let transition = self.net.add_transition(name);
+                    if let Some(condition) = element.structure_of("condition") {
+                        let guard = self.guard(condition)?;
+                        transition.guard(&mut self.net, guard)?;
+                    }
+                    self.register(id, ColouredNode::Transition(transition))?;
+                }
+                "referencePlace" | "referenceTransition" => {
+                    self.references.insert(
+                        element.attribute("id")?.to_string(),
+                        element.attribute("ref")?.to_string(),
+                    );
+                }
+                "arc" => self.arcs.push(element),
+                "page" => self.read_page(element)?,
+                _ => {}
+            }
+        }
+        Ok(())
+    }
+
+    fn register(&mut self, id: &str, node: ColouredNode) -> Result<()> {
+        if self.nodes.insert(id.to_string(), node).is_some() {
+            return Err(PetriError::CorruptedData(format!(
+                "node id \"{}\" is not unique",
+                id
+            )));
+        }
+        Ok(())
+    }
+
+    /// arcs without inscription carry one token of dot places
+    fn connect(&mut self) -> Result<()> {
+        for arc in std::mem::take(&mut self.arcs) {
+            let id = arc.attribute("id")?;
+            let source = resolve(&self.nodes, &self.references, arc.attribute("source")?)?;
+            let target = resolve(&self.nodes, &self.references, arc.attribute("target")?)?;
+            let (place, transition, produce) = match (source, target) {
+                (ColouredNode::Place(place), ColouredNode::Transition(transition)) => {
+                    (place, transition, false)
+                }
+                (ColouredNode::Transition(transition), ColouredNode::Place(place)) => {
+                    (place, transition, true)
+                }
+                _ => {
+                    return Err(PetriError::CorruptedData(format!(
+                        "arc \"{}\" connects nodes of the same kind",
+                        id
+                    )))
+                }
+            };
+            let expression = match arc.structure_of("hlinscription") {
+                Some(inscription) => self.multiset(inscription)?,
+                None if *self.net.sort(self.net.place(place)?.sort)? == Sort::Dot => {
+                    ArcExpression::Term(Term::Constant(Colour::Dot))
+                }
+                None => {
+                    return Err(PetriError::CorruptedData(format!(
+                        "arc \"{}\" has no inscription",
+                        id
+                    )))
+                }
+            };
+            if produce {
+                self.net.add_output_arc(transition, place, expression)?;
+            } else {
+                self.net.add_input_arc(place, transition, expression)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// `all` always refers to the sort of the place
+    fn multiset(&self, element: &XmlElement) -> Result<ArcExpression> {
+        Ok(match element.name.as_str() {
+            "numberof" => {
+                let operands = element.operands(2)?;
+                if operands[0].name != "numberconstant" {
+                    return Err(PetriError::UnsupportedFeature(format!(
+                        "symmetric net multiplicity \"{}\"",
+                        operands[0].name
+                    )));
+                }
+                let count = operands[0].integer_attribute("value")?;
+                let count = usize::try_from(count).map_err(|_| {
+                    PetriError::CorruptedData(format!("negative multiplicity {}", count))
+                })?;
+                ArcExpression::Scaled(count, Box::new(self.multiset(operands[1])?))
+            }
+            "all" => ArcExpression::All,
+            "add" => ArcExpression::Sum(
+                element
+                    .subterms()
+                    .map(|subterm| self.multiset(subterm))
+                    .collect::<Result<_>>()?,
+            ),
+            _ => ArcExpression::Term(self.term(element)?),
+        })
+    }
+
+    fn term(&self, element: &XmlElement) -> Result<Term> {
+        Ok(match element.name.as_str() {
+            "variable" => {
+                let id = element.attribute("refvariable")?;
+                let variable = self.variables.get(id).ok_or_else(|| {
+                    PetriError::CorruptedData(format!("reference to unknown variable \"{}\"", id))
+                })?;
+                Term::Variable(*variable)
+            }
+            "useroperator" => {
+                let id = element.attribute("declaration")?;
+                let constant = self.constants.get(id).ok_or_else(|| {
+                    PetriError::UnsupportedFeature(format!("symmetric net operator \"{}\"", id))
+                })?;
+                Term::Constant(constant.clone())
+            }
+            "dotconstant" => Term::Constant(Colour::Dot),
+            "finiteintrangeconstant" => {
+                Term::Constant(Colour::Integer(element.integer_attribute("value")?))
+            }
+            "successor" => Term::Successor(Box::new(self.term(element.operands(1)?[0])?)),
+            "predecessor" => Term::Predecessor(Box::new(self.term(element.operands(1)?[0])?)),
+            "tuple" => Term::Tuple(
+                element
+                    .subterms()
+                    .map(|subterm| self.term(subterm))
+                    .collect::<Result<_>>()?,
+            ),
+            other => {
+                return Err(PetriError::UnsupportedFeature(format!(
+                    "symmetric net term \"{}\"",
+                    other
+                )))
+            }
+        })
+    }
+
+    fn guard(&self, element: &XmlElement) -> Result<Guard> {
+        let comparison = |guard: fn(Term, Term) -> Guard| -> Result<Guard> {
+            let operands = element.operands(2)?;
+            Ok(guard(self.term(operands[0])?, self.term(operands[1])?))
+        };
+        let guards = || -> Result<Vec<Guard>> {
+            element
+                .subterms()
+                .map(|subterm| self.guard(subterm))
+                .collect()
+        };
+        Ok(match element.name.as_str() {
+            "booleanconstant" => match element.attribute("value")? {
+                "true" => Guard::True,
+                "false" => Guard::Or(Vec::new()),
+                other => {
+                    return Err(PetriError::CorruptedData(format!(
+                        "\"{}\" is not a boolean constant",
+                        other
+                    )))
+                }
+            },
+            "and" => Guard::And(guards()?),
+            "or" => Guard::Or(guards()?),
+            "not" => Guard::Not(Box::new(self.guard(element.operands(1)?[0])?)),
+            "imply" => {
+                let operands = element.operands(2)?;
+                Guard::Or(vec![
+                    Guard::Not(Box::new(self.guard(operands[0])?)),
+                    self.guard(operands[1])?,
+                ])
+            }
+            "equality" => comparison(Guard::Equal)?,
+            "inequality" => comparison(Guard::NotEqual)?,
+            "lessthan" => comparison(Guard::LessThan)?,
+            "lessthanorequal" => comparison(Guard::LessThanOrEqual)?,
+            "greaterthan" => comparison(Guard::GreaterThan)?,
+            "greaterthanorequal" => comparison(Guard::GreaterThanOrEqual)?,
+            other => {
+                return Err(PetriError::UnsupportedFeature(format!(
+                    "symmetric net condition \"{}\"",
+                    other
+                )))
+            }
+        })
+    }
+}
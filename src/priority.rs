@@ -0,0 +1,54 @@
+use crate::{PetriError, PetriNet, Result, TransitionRef};
+use std::collections::BTreeSet;
+
+impl PetriNet {
+    /// Gives `higher` priority over `lower`: `lower` is not enabled in
+    /// markings where `higher` could fire. Priorities are transitive, a
+    /// transition is also blocked by the transitions above its direct
+    /// superiors.
+    /// Fails with `InvalidData` if the priority would form a cycle.
+    pub fn add_priority(&mut self, higher: TransitionRef, lower: TransitionRef) -> Result<()> {
+        self.transition(higher)?;
+        self.transition(lower)?;
+        if higher == lower || self.higher_priorities(higher).contains(&lower) {
+            return Err(PetriError::InvalidData(format!(
+                "priority of t_{} over t_{} would form a cycle",
+                higher.index, lower.index
+            )));
+        }
+        self.priorities.insert((higher, lower));
+        Ok(())
+    }
+
+    pub fn remove_priority(&mut self, higher: TransitionRef, lower: TransitionRef) -> Result<()> {
+        if self.priorities.remove(&(higher, lower)) {
+            Ok(())
+        } else {
+            Err(PetriError::ObjectNotFound)
+        }
+    }
+
+    /// (higher, lower) pairs ordered by the higher transition, without the
+    /// transitive ones
+    pub fn priorities(&self) -> impl Iterator<Item = (TransitionRef, TransitionRef)> + '_ {
+        self.priorities.iter().copied()
+    }
+
+    pub(crate) fn has_priorities(&self) -> bool {
+        !self.priorities.is_empty()
+    }
+
+    /// all transitions with a direct or transitive priority over the transition
+    pub(crate) fn higher_priorities(&self, transition: TransitionRef) -> BTreeSet<TransitionRef> {
+        let mut higher = BTreeSet::new();
+        let mut pending = vec![transition];
+        while let Some(lower) = pending.pop() {
+            for (superior, _) in self.priorities.iter().filter(|(_, other)| *other == lower) {
+                if higher.insert(*superior) {
+                    pending.push(*superior);
+                }
+            }
+        }
+        higher
+    }
+}
@@ -0,0 +1,34 @@
+use crate::{Ctl, ExplorationLimits, Ltl, PetriNet, Result, Verdict};
+
+/// Question about the reachable markings or the runs of a net, which can be
+/// checked directly or written as a LoLA formula.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Property {
+    /// some reachable marking satisfies the formula, `EF`
+    Reachable(Ctl),
+    /// all reachable markings satisfy the formula, `AG`
+    Invariant(Ctl),
+    Ctl(Ctl),
+    /// all runs from the initial marking satisfy the formula
+    Ltl(Ltl),
+}
+
+impl PetriNet {
+    /// Checks the property with the CTL or the LTL model checker.
+    pub fn check_property(
+        &self,
+        property: &Property,
+        limits: &ExplorationLimits,
+    ) -> Result<Verdict> {
+        match property {
+            Property::Reachable(formula) => {
+                self.check_ctl(&Ctl::ExistsFinally(Box::new(formula.clone())), limits)
+            }
+            Property::Invariant(formula) => {
+                self.check_ctl(&Ctl::AllGlobally(Box::new(formula.clone())), limits)
+            }
+            Property::Ctl(formula) => self.check_ctl(formula, limits),
+            Property::Ltl(formula) => self.check_ltl(formula, limits),
+        }
+    }
+}
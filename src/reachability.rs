@@ -0,0 +1,244 @@
+use crate::{Marking, PetriNet, Result, TransitionRef};
+use std::collections::{HashMap, VecDeque};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SearchOrder {
+    BreadthFirst,
+    DepthFirst,
+}
+
+/// Budget of a state space exploration. Limits that are `None` are not checked.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ExplorationLimits {
+    pub order: SearchOrder,
+    pub max_states: Option<usize>,
+    /// estimated size of the stored markings and edges in bytes
+    pub max_memory: Option<usize>,
+}
+
+impl Default for ExplorationLimits {
+    fn default() -> Self {
+        ExplorationLimits {
+            order: SearchOrder::BreadthFirst,
+            max_states: None,
+            max_memory: None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Edge {
+    pub source: usize,
+    pub transition: TransitionRef,
+    pub target: usize,
+}
+
+/// Reachable markings of a net connected by the transitions that lead from
+/// one to another. States are identified by their index in `markings`,
+/// the initial marking has index 0.
+#[derive(Debug, Clone)]
+pub struct ReachabilityGraph {
+    markings: Vec<Marking>,
+    /// state of every marking
+    states: HashMap<Marking, usize>,
+    edges: Vec<Edge>,
+    /// outgoing edge indices per state
+    successors: Vec<Vec<usize>>,
+    /// edge over which a state was discovered first
+    parents: Vec<Option<usize>>,
+    /// states whose successors were all added
+    explored: Vec<bool>,
+    complete: bool,
+}
+
+impl ReachabilityGraph {
+    /// false if the exploration stopped at a limit before all reachable markings
+    /// were found. Markings that were not explored have no outgoing edges then.
+    pub fn is_complete(&self) -> bool {
+        self.complete
+    }
+
+    /// true if all successors of the state are part of the graph, always
+    /// the case for complete graphs
+    pub fn is_explored(&self, state: usize) -> bool {
+        self.explored.get(state).copied().unwrap_or(false)
+    }
+
+    pub fn markings(&self) -> &[Marking] {
+        &self.markings
+    }
+
+    pub fn edges(&self) -> &[Edge] {
+        &self.edges
+    }
+
+    pub fn state(&self, marking: &Marking) -> Option<usize> {
+        self.states.get(marking).copied()
+    }
+
+    pub fn successors(&self, state: usize) -> impl Iterator<Item = &Edge> {
+        self.successors
+            .get(state)
+            .into_iter()
+            .flatten()
+            .map(move |edge| &self.edges[*edge])
+    }
+
+    /// Transitions that lead from the initial marking to the given state.
+    /// For breadth first explorations this is a shortest firing sequence.
+    pub fn path_to(&self, state: usize) -> Option<Vec<TransitionRef>> {
+        if state >= self.markings.len() {
+            return None;
+        }
+        let mut path = Vec::new();
+        let mut current = state;
+        while let Some(edge) = self.parents[current] {
+            let edge = &self.edges[edge];
+            path.push(edge.transition);
+            current = edge.source;
+        }
+        path.reverse();
+        Some(path)
+    }
+
+    /// Strongly connected components with Tarjan's algorithm, every state
+    /// belongs to exactly one. Components are listed in reverse topological
+    /// order: edges only lead to components earlier in the list.
+    pub fn strongly_connected_components(&self) -> Vec<Vec<usize>> {
+        let count = self.markings.len();
+        let mut index: Vec<Option<usize>> = vec![None; count];
+        let mut lowlink = vec![0; count];
+        let mut on_stack = vec![false; count];
+        let mut stack = Vec::new();
+        let mut components = Vec::new();
+        let mut next = 0;
+        for root in 0..count {
+            if index[root].is_some() {
+                continue;
+            }
+            // the recursion of the algorithm as (state, next successor) frames
+            let mut calls = vec![(root, 0)];
+            index[root] = Some(next);
+            lowlink[root] = next;
+            next += 1;
+            stack.push(root);
+            on_stack[root] = true;
+            while let Some(frame) = calls.last_mut() {
+                let state = frame.0;
+                if let Some(edge) = self.successors[state].get(frame.1) {
+                    frame.1 += 1;
+                    let target = self.edges[*edge].target;
+                    match index[target] {
+                        None => {
+                            index[target] = Some(next);
+                            lowlink[target] = next;
+                            next += 1;
+                            stack.push(target);
+                            on_stack[target] = true;
+                            calls.push((target, 0));
+                        }
+                        Some(target_index) if on_stack[target] => {
+                            lowlink[state] = lowlink[state].min(target_index);
+                        }
+                        Some(_) => {}
+                    }
+                    continue;
+                }
+                calls.pop();
+                if let Some((parent, _)) = calls.last() {
+                    lowlink[*parent] = lowlink[*parent].min(lowlink[state]);
+                }
+                if Some(lowlink[state]) == index[state] {
+                    let mut component = Vec::new();
+                    while let Some(member) = stack.pop() {
+                        on_stack[member] = false;
+                        component.push(member);
+                        if member == state {
+                            break;
+                        }
+                    }
+                    component.sort_unstable();
+                    components.push(component);
+                }
+            }
+        }
+        components
+    }
+
+    fn insert(&mut self, marking: Marking, parent: Option<usize>) -> usize {
+        self.states.insert(marking.clone(), self.markings.len());
+        self.markings.push(marking);
+        self.successors.push(Vec::new());
+        self.parents.push(parent);
+        self.explored.push(false);
+        self.markings.len() - 1
+    }
+}
+
+impl PetriNet {
+    pub fn reachability_graph(&self, limits: &ExplorationLimits) -> Result<ReachabilityGraph> {
+        let mut graph = ReachabilityGraph {
+            markings: Vec::new(),
+            states: HashMap::new(),
+            edges: Vec::new(),
+            successors: Vec::new(),
+            parents: Vec::new(),
+            explored: Vec::new(),
+            complete: true,
+        };
+        let initial = self.initial_marking();
+        // markings are stored in the graph and the lookup table
+        let marking_size =
+            2 * (std::mem::size_of::<Marking>() + self.places.len() * std::mem::size_of::<usize>());
+        let edge_size = std::mem::size_of::<Edge>() + 2 * std::mem::size_of::<usize>();
+        let mut memory = marking_size;
+        graph.insert(initial, None);
+        let mut pending = VecDeque::new();
+        pending.push_back(0);
+        'explore: while let Some(state) = match limits.order {
+            SearchOrder::BreadthFirst => pending.pop_front(),
+            SearchOrder::DepthFirst => pending.pop_back(),
+        } {
+            let marking = graph.markings[state].clone();
+            for transition in self.enabled_transitions(&marking) {
+                let mut successor = marking.clone();
+                transition.fire(self, &mut successor)?;
+                let target = match graph.states.get(&successor) {
+                    Some(target) => *target,
+                    None => {
+                        let exceeds_states = limits
+                            .max_states
+                            .is_some_and(|max| graph.markings.len() >= max);
+                        let exceeds_memory = limits
+                            .max_memory
+                            .is_some_and(|max| memory + marking_size + edge_size > max);
+                        if exceeds_states || exceeds_memory {
+                            graph.complete = false;
+                            break 'explore;
+                        }
+                        memory += marking_size;
+                        let target = graph.insert(successor, Some(graph.edges.len()));
+                        pending.push_back(target);
+                        target
+                    }
+                };
+                if limits
+                    .max_memory
+                    .is_some_and(|max| memory + edge_size > max)
+                {
+                    graph.complete = false;
+                    break 'explore;
+                }
+                memory += edge_size;
+                graph.successors[state].push(graph.edges.len());
+                graph.edges.push(Edge {
+                    source: state,
+                    transition,
+                    target,
+                });
+            }
+            graph.explored[state] = true;
+        }
+        Ok(graph)
+    }
+}
@@ -0,0 +1,274 @@
+use crate::{Marking, PetriError, PetriNet, PlaceRef, Result, TransitionRef};
+use std::collections::HashSet;
+
+/// Firing behaviour of a transition in a generalized stochastic Petri net.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum StochasticKind {
+    /// fires after an exponentially distributed delay with the given rate
+    Exponential { rate: f64 },
+    /// fires without delay as soon as it is enabled, before any exponential
+    /// transition. Conflicts between enabled immediate transitions are
+    /// resolved randomly in proportion to their weights.
+    Immediate { weight: f64 },
+}
+
+impl TransitionRef {
+    /// Fails with `InvalidData` if the rate or weight is not positive and finite.
+    pub fn stochastic(self, net: &mut PetriNet, kind: Option<StochasticKind>) -> Result<()> {
+        if let Some(kind) = kind {
+            let value = match kind {
+                StochasticKind::Exponential { rate } => rate,
+                StochasticKind::Immediate { weight } => weight,
+            };
+            if !(value.is_finite() && value > 0.0) {
+                return Err(PetriError::InvalidData(format!(
+                    "rates and weights have to be positive but found {}",
+                    value
+                )));
+            }
+        }
+        net.transition_mut(self)?.stochastic = kind;
+        Ok(())
+    }
+}
+
+/// End of a simulation run. Limits that are `None` are not checked, a run
+/// without limits only ends in a dead marking.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct SimulationLimits {
+    pub end_time: Option<f64>,
+    pub max_steps: Option<usize>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Step {
+    pub time: f64,
+    pub transition: TransitionRef,
+    /// marking after the transition fired
+    pub marking: Marking,
+}
+
+/// Markings of a simulation run over time, starting with the initial
+/// marking at time zero.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Trajectory {
+    initial: Marking,
+    steps: Vec<Step>,
+    end_time: f64,
+}
+
+impl Trajectory {
+    pub fn initial_marking(&self) -> &Marking {
+        &self.initial
+    }
+
+    pub fn steps(&self) -> &[Step] {
+        &self.steps
+    }
+
+    /// the end time of the limits if the next delay would have passed it or
+    /// the run reached a dead marking, otherwise the time of the last step
+    pub fn end_time(&self) -> f64 {
+        self.end_time
+    }
+
+    /// the marking at the given time, after all steps up to this time
+    pub fn marking_at(&self, time: f64) -> &Marking {
+        self.steps
+            .iter()
+            .take_while(|step| step.time <= time)
+            .last()
+            .map_or(&self.initial, |step| &step.marking)
+    }
+
+    /// firings of the transition per time unit
+    pub fn throughput(&self, transition: TransitionRef) -> f64 {
+        if self.end_time <= 0.0 {
+            return 0.0;
+        }
+        let firings = self
+            .steps
+            .iter()
+            .filter(|step| step.transition == transition)
+            .count();
+        firings as f64 / self.end_time
+    }
+
+    /// Average number of tokens on the place weighted by the time the
+    /// markings lasted. Markings of immediate transitions do not last.
+    pub fn mean_tokens(&self, place: PlaceRef) -> f64 {
+        if self.end_time <= 0.0 {
+            return self.initial.tokens(place) as f64;
+        }
+        let mut sum = 0.0;
+        let mut time = 0.0;
+        let mut marking = &self.initial;
+        for step in &self.steps {
+            sum += marking.tokens(place) as f64 * (step.time - time);
+            time = step.time;
+            marking = &step.marking;
+        }
+        sum += marking.tokens(place) as f64 * (self.end_time - time);
+        sum / self.end_time
+    }
+}
+
+impl PetriNet {
+    /// Simulates the net with Gillespie's algorithm, equal seeds lead to equal
+    /// trajectories. Fails with `InvalidData` if a transition has no
+    /// stochastic firing behaviour.
+    ///
+    /// Time does not pass while immediate transitions fire, so `end_time`
+    /// cannot stop them. Fails with `UnsupportedFeature` if they fire in a
+    /// loop that never reaches a tangible marking. Immediate transitions that
+    /// produce tokens without bound are not detected, `max_steps` ends such runs.
+    pub fn simulate(&self, limits: &SimulationLimits, seed: u64) -> Result<Trajectory> {
+        let kinds = self.stochastic_kinds()?;
+        let mut random = SplitMix64(seed);
+        let mut marking = self.initial_marking();
+        let mut trajectory = Trajectory {
+            initial: marking.clone(),
+            steps: Vec::new(),
+            end_time: 0.0,
+        };
+        let mut time = 0.0;
+        // vanishing markings since time last passed and those known to be left
+        let mut vanishing = HashSet::new();
+        let mut escaping = HashSet::new();
+        loop {
+            if limits
+                .max_steps
+                .is_some_and(|max| trajectory.steps.len() >= max)
+            {
+                break;
+            }
+            let mut immediate = Vec::new();
+            let mut exponential = Vec::new();
+            for (transition, kind) in &kinds {
+                if transition.is_enabled(self, &marking)? {
+                    match kind {
+                        StochasticKind::Immediate { weight } => {
+                            immediate.push((*transition, *weight))
+                        }
+                        StochasticKind::Exponential { rate } => {
+                            exponential.push((*transition, *rate))
+                        }
+                    }
+                }
+            }
+            let transition = if !immediate.is_empty() {
+                if !vanishing.insert(marking.clone()) && !escaping.contains(&marking) {
+                    if !self.leaves_vanishing(&kinds, &marking)? {
+                        return Err(PetriError::UnsupportedFeature(
+                            "immediate transitions that fire forever without reaching a tangible marking".into(),
+                        ));
+                    }
+                    escaping.insert(marking.clone());
+                }
+                random.choose(&immediate)
+            } else if !exponential.is_empty() {
+                let total: f64 = exponential.iter().map(|(_, rate)| rate).sum();
+                // 1 - u lies in (0, 1]
+                let delay = -(1.0 - random.next_f64()).ln() / total;
+                if let Some(end) = limits.end_time.filter(|end| time + delay > *end) {
+                    time = end;
+                    break;
+                }
+                time += delay;
+                vanishing.clear();
+                random.choose(&exponential)
+            } else {
+                // a dead marking lasts until the end of the run
+                if let Some(end) = limits.end_time {
+                    time = end;
+                }
+                break;
+            };
+            transition.fire(self, &mut marking)?;
+            trajectory.steps.push(Step {
+                time,
+                transition,
+                marking: marking.clone(),
+            });
+        }
+        trajectory.end_time = time;
+        Ok(trajectory)
+    }
+
+    /// true if firing immediate transitions from the vanishing marking can
+    /// reach a marking that enables none of them
+    fn leaves_vanishing(
+        &self,
+        kinds: &[(TransitionRef, StochasticKind)],
+        marking: &Marking,
+    ) -> Result<bool> {
+        let mut visited = HashSet::new();
+        visited.insert(marking.clone());
+        let mut pending = vec![marking.clone()];
+        while let Some(marking) = pending.pop() {
+            let mut vanishing = false;
+            for (transition, kind) in kinds {
+                if let StochasticKind::Immediate { .. } = kind {
+                    if transition.is_enabled(self, &marking)? {
+                        vanishing = true;
+                        let mut successor = marking.clone();
+                        transition.fire(self, &mut successor)?;
+                        if visited.insert(successor.clone()) {
+                            pending.push(successor);
+                        }
+                    }
+                }
+            }
+            if !vanishing {
+                return Ok(true);
+            }
+        }
+        Ok(false)
+    }
+
+    /// Firing behaviour of all transitions, fails with `InvalidData` if a
+    /// transition has none.
+    pub(crate) fn stochastic_kinds(&self) -> Result<Vec<(TransitionRef, StochasticKind)>> {
+        self.transitions()
+            .map(|transition| match self.transition(transition)?.stochastic {
+                Some(kind) => Ok((transition, kind)),
+                None => Err(PetriError::InvalidData(format!(
+                    "transition t_{} has no stochastic firing behaviour",
+                    transition.index
+                ))),
+            })
+            .collect()
+    }
+}
+
+/// Small seedable generator, the quality is sufficient for simulations.
+struct SplitMix64(u64);
+
+impl SplitMix64 {
+    fn next_u64(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    /// uniformly distributed in [0, 1)
+    fn next_f64(&mut self) -> f64 {
+        (self.next_u64() >> 11) as f64 / (1u64 << 53) as f64
+    }
+
+    /// one of the transitions with a probability proportional to its weight
+    fn choose(&mut self, weighted: &[(TransitionRef, f64)]) -> TransitionRef {
+        let total: f64 = weighted.iter().map(|(_, weight)| weight).sum();
+        let mut target = self.next_f64() * total;
+        for (transition, weight) in weighted {
+            if target < *weight {
+                return *transition;
+            }
+            target -= weight;
+        }
+        // rounding errors can leave a tiny rest
+        weighted[weighted.len() - 1].0
+    }
+}
@@ -0,0 +1,328 @@
+use crate::{
+    Edge, ExplorationLimits, Marking, PetriError, PetriNet, Result, SearchOrder, TransitionRef,
+};
+use std::collections::{HashMap, VecDeque};
+
+/// Static firing interval `[earliest, latest]` of a transition in a time
+/// Petri net. Once enabled, the transition cannot fire before `earliest` time
+/// units passed and has to fire or get disabled until `latest` passed.
+/// `latest` is `None` for intervals that are open to the right.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct TimeInterval {
+    pub earliest: usize,
+    pub latest: Option<usize>,
+}
+
+impl TimeInterval {
+    /// Fails with `InvalidData` if `earliest` is larger than `latest`.
+    pub fn new(earliest: usize, latest: Option<usize>) -> Result<Self> {
+        if latest.is_some_and(|latest| earliest > latest) {
+            return Err(PetriError::InvalidData(format!(
+                "time interval [{}, {}] is empty",
+                earliest,
+                latest.unwrap_or_default()
+            )));
+        }
+        Ok(TimeInterval { earliest, latest })
+    }
+}
+
+impl Default for TimeInterval {
+    /// `[0, ∞)`, the interval of transitions without timing constraints
+    fn default() -> Self {
+        TimeInterval {
+            earliest: 0,
+            latest: None,
+        }
+    }
+}
+
+impl TransitionRef {
+    /// `None` removes the interval, the transition can fire at any time then.
+    /// Only the state class graph takes intervals into account.
+    pub fn interval(self, net: &mut PetriNet, interval: Option<TimeInterval>) -> Result<()> {
+        if let Some(interval) = interval {
+            TimeInterval::new(interval.earliest, interval.latest)?;
+        }
+        net.transition_mut(self)?.interval = interval;
+        Ok(())
+    }
+}
+
+/// upper bound of a difference between two firing times, `None` is infinite
+type Bound = Option<i64>;
+
+/// Marking together with the possible firing times of the enabled
+/// transitions, relative to the moment the class was entered.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct StateClass {
+    marking: Marking,
+    /// enabled transitions in index order
+    enabled: Vec<TransitionRef>,
+    /// Canonical difference bound matrix: entry `(i, j)` bounds `θi - θj`
+    /// where `θi` is the firing time of `enabled[i - 1]` and `θ0` is zero.
+    bounds: Vec<Vec<Bound>>,
+}
+
+impl StateClass {
+    pub fn marking(&self) -> &Marking {
+        &self.marking
+    }
+
+    pub fn enabled(&self) -> &[TransitionRef] {
+        &self.enabled
+    }
+
+    /// earliest and latest firing time of an enabled transition, measured
+    /// from the moment the class was entered
+    pub fn firing_interval(&self, transition: TransitionRef) -> Option<TimeInterval> {
+        let index = self.enabled.binary_search(&transition).ok()? + 1;
+        Some(TimeInterval {
+            earliest: (-self.bounds[0][index].unwrap_or(0)).max(0) as usize,
+            latest: self.bounds[index][0].map(|latest| latest as usize),
+        })
+    }
+
+    /// the domain restricted to runs where `enabled[index]` fires first,
+    /// `None` if the transition cannot fire first
+    fn fire_first(&self, index: usize) -> Option<Vec<Vec<Bound>>> {
+        let mut bounds = self.bounds.clone();
+        let fired = index + 1;
+        for bound in bounds[fired].iter_mut().skip(1) {
+            *bound = min(*bound, Some(0));
+        }
+        if close(&mut bounds) {
+            Some(bounds)
+        } else {
+            None
+        }
+    }
+}
+
+/// Berthomieu-Diaz state class graph of a time Petri net. Classes are
+/// identified by their index in `classes`, the initial class has index 0.
+/// It is finite if the underlying untimed net is bounded.
+#[derive(Debug, Clone)]
+pub struct StateClassGraph {
+    classes: Vec<StateClass>,
+    edges: Vec<Edge>,
+    /// outgoing edge indices per class
+    successors: Vec<Vec<usize>>,
+    complete: bool,
+}
+
+impl StateClassGraph {
+    /// false if the construction stopped at a limit before all classes were
+    /// found. Classes that were not explored have no outgoing edges then.
+    pub fn is_complete(&self) -> bool {
+        self.complete
+    }
+
+    pub fn classes(&self) -> &[StateClass] {
+        &self.classes
+    }
+
+    pub fn edges(&self) -> &[Edge] {
+        &self.edges
+    }
+
+    pub fn successors(&self, class: usize) -> impl Iterator<Item = &Edge> {
+        self.successors
+            .get(class)
+            .into_iter()
+            .flatten()
+            .map(move |edge| &self.edges[*edge])
+    }
+}
+
+impl PetriNet {
+    /// Transitions without an interval can fire at any time (`[0, ∞)`).
+    /// Firing a transition resets the clock of every transition that was not
+    /// enabled during the firing, including the fired one.
+    /// Fails with `UnsupportedFeature` for nets with priorities, they also
+    /// depend on the firing times.
+    pub fn state_class_graph(&self, limits: &ExplorationLimits) -> Result<StateClassGraph> {
+        if self.has_priorities() {
+            return Err(PetriError::UnsupportedFeature(
+                "state class graphs of nets with priorities".into(),
+            ));
+        }
+        let mut graph = StateClassGraph {
+            classes: Vec::new(),
+            edges: Vec::new(),
+            successors: Vec::new(),
+            complete: true,
+        };
+        let initial = self.state_class(self.initial_marking(), &[], None)?;
+        let edge_size = std::mem::size_of::<Edge>() + std::mem::size_of::<usize>();
+        let mut memory = self.class_size(&initial);
+        let mut known = HashMap::new();
+        known.insert(initial.clone(), 0);
+        graph.classes.push(initial);
+        graph.successors.push(Vec::new());
+        let mut pending = VecDeque::new();
+        pending.push_back(0);
+        'explore: while let Some(class) = match limits.order {
+            SearchOrder::BreadthFirst => pending.pop_front(),
+            SearchOrder::DepthFirst => pending.pop_back(),
+        } {
+            let current = graph.classes[class].clone();
+            for (index, transition) in current.enabled.iter().enumerate() {
+                let bounds = match current.fire_first(index) {
+                    Some(bounds) => bounds,
+                    None => continue,
+                };
+                let successor = self.successor_class(&current, *transition, &bounds)?;
+                let target = match known.get(&successor) {
+                    Some(target) => *target,
+                    None => {
+                        let size = self.class_size(&successor);
+                        let exceeds_states = limits
+                            .max_states
+                            .is_some_and(|max| graph.classes.len() >= max);
+                        let exceeds_memory = limits
+                            .max_memory
+                            .is_some_and(|max| memory + size + edge_size > max);
+                        if exceeds_states || exceeds_memory {
+                            graph.complete = false;
+                            break 'explore;
+                        }
+                        memory += size;
+                        graph.classes.push(successor.clone());
+                        graph.successors.push(Vec::new());
+                        let target = graph.classes.len() - 1;
+                        known.insert(successor, target);
+                        pending.push_back(target);
+                        target
+                    }
+                };
+                if limits
+                    .max_memory
+                    .is_some_and(|max| memory + edge_size > max)
+                {
+                    graph.complete = false;
+                    break 'explore;
+                }
+                memory += edge_size;
+                graph.successors[class].push(graph.edges.len());
+                graph.edges.push(Edge {
+                    source: class,
+                    transition: *transition,
+                    target,
+                });
+            }
+        }
+        Ok(graph)
+    }
+
+    /// Fires `transition` from `class` with the firing domain `bounds` that
+    /// was restricted to the transition firing first.
+    fn successor_class(
+        &self,
+        class: &StateClass,
+        transition: TransitionRef,
+        bounds: &[Vec<Bound>],
+    ) -> Result<StateClass> {
+        let fired = class
+            .enabled
+            .binary_search(&transition)
+            .expect("fired transitions are enabled")
+            + 1;
+        // transitions that stay enabled while the consumed tokens are missing
+        // keep their clocks
+        let mut intermediate = class.marking.clone();
+        for (place, weight) in self.weights(transition)?.0 {
+            intermediate.set_tokens(place, intermediate.tokens(place) - weight)?;
+        }
+        let mut persistent = Vec::new();
+        for (index, other) in class.enabled.iter().enumerate() {
+            if *other != transition && other.is_enabled(self, &intermediate)? {
+                persistent.push((*other, index + 1));
+            }
+        }
+        let mut marking = class.marking.clone();
+        transition.fire(self, &mut marking)?;
+        self.state_class(marking, &persistent, Some((bounds, fired)))
+    }
+
+    /// Class of a marking. `persistent` lists transitions with their variable
+    /// in `previous`, whose firing times are shifted by the time of the fired
+    /// variable. All other enabled transitions start with their static interval.
+    fn state_class(
+        &self,
+        marking: Marking,
+        persistent: &[(TransitionRef, usize)],
+        previous: Option<(&[Vec<Bound>], usize)>,
+    ) -> Result<StateClass> {
+        let enabled: Vec<TransitionRef> = self.enabled_transitions(&marking).collect();
+        let size = enabled.len() + 1;
+        let mut bounds = vec![vec![None; size]; size];
+        // variable of every enabled transition in the previous class
+        let mut origins = vec![None; size];
+        for (index, transition) in enabled.iter().enumerate() {
+            let variable = index + 1;
+            bounds[variable][variable] = Some(0);
+            match persistent.iter().find(|(other, _)| other == transition) {
+                Some((_, origin)) => origins[variable] = Some(*origin),
+                None => {
+                    let interval = self.transition(*transition)?.interval.unwrap_or_default();
+                    bounds[variable][0] = interval.latest.map(|latest| latest as i64);
+                    bounds[0][variable] = Some(-(interval.earliest as i64));
+                }
+            }
+        }
+        bounds[0][0] = Some(0);
+        if let Some((previous, fired)) = previous {
+            // θ'i = θi - θf for persistent transitions
+            for i in 1..size {
+                if let Some(origin_i) = origins[i] {
+                    bounds[i][0] = previous[origin_i][fired];
+                    bounds[0][i] = previous[fired][origin_i];
+                    for j in 1..size {
+                        if let Some(origin_j) = origins[j] {
+                            bounds[i][j] = previous[origin_i][origin_j];
+                        }
+                    }
+                }
+            }
+        }
+        close(&mut bounds);
+        Ok(StateClass {
+            marking,
+            enabled,
+            bounds,
+        })
+    }
+
+    /// estimated size of a class in the graph and the lookup table in bytes
+    fn class_size(&self, class: &StateClass) -> usize {
+        let variables = class.bounds.len();
+        2 * (std::mem::size_of::<StateClass>()
+            + self.places.len() * std::mem::size_of::<usize>()
+            + class.enabled.len() * std::mem::size_of::<TransitionRef>()
+            + variables * variables * std::mem::size_of::<Bound>())
+    }
+}
+
+fn min(a: Bound, b: Bound) -> Bound {
+    match (a, b) {
+        (Some(a), Some(b)) => Some(a.min(b)),
+        (Some(a), None) | (None, Some(a)) => Some(a),
+        (None, None) => None,
+    }
+}
+
+/// Tightens all bounds (Floyd-Warshall), false if the bounds are contradictory.
+fn close(bounds: &mut [Vec<Bound>]) -> bool {
+    let size = bounds.len();
+    for k in 0..size {
+        for i in 0..size {
+            for j in 0..size {
+                if let (Some(ik), Some(kj)) = (bounds[i][k], bounds[k][j]) {
+                    bounds[i][j] = min(bounds[i][j], Some(ik + kj));
+                }
+            }
+        }
+    }
+    (0..size).all(|i| bounds[i][i].is_some_and(|bound| bound >= 0))
+}
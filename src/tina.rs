@@ -0,0 +1,148 @@
+use crate::names::NodeIds;
+use crate::{ArcKind, PetriError, PetriNet, TimeInterval};
+
+const PLACE_PREFIX: &str = "p_";
+const TRANSITION_PREFIX: &str = "t_";
+
+impl PetriNet {
+    /// Writes the net in the textual `.net` format of the TINA toolbox.
+    /// Fails with `InvalidInput` if the net has reset arcs or capacities,
+    /// the inner error is a `PetriError::UnsupportedFeature`.
+    pub fn to_tina_string(&self) -> Result<String, std::io::Error> {
+        let mut writer = Vec::new();
+        self.write_tina(&mut writer)?;
+        Ok(String::from_utf8(writer).expect("Document generated non UTF-8 string"))
+    }
+
+    pub fn to_tina<T>(&self, writer: &mut T) -> Result<(), std::io::Error>
+    where
+        T: std::io::Write,
+    {
+        if self.print_unconnected_nodes().is_err() {
+            panic!("Cannot find all nodes: this is a bug!");
+        };
+        self.write_tina(writer)?;
+        Ok(())
+    }
+
+    fn write_tina<T>(&self, writer: &mut T) -> Result<(), std::io::Error>
+    where
+        T: std::io::Write,
+    {
+        // complementary places can replace capacities beforehand
+        let unsupported = if self.uses_arc_kind(ArcKind::Reset) {
+            Some("TINA does not support reset arcs")
+        } else if self.has_capacities() {
+            Some("TINA does not support capacities")
+        } else {
+            None
+        };
+        if let Some(feature) = unsupported {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidInput,
+                PetriError::UnsupportedFeature(feature.into()),
+            ));
+        }
+        let ids = self.node_ids(PLACE_PREFIX, TRANSITION_PREFIX);
+        writer.write_all("net petrinet\n".as_bytes())?;
+        self.write_tina_places(writer, &ids)?;
+        self.write_tina_transitions(writer, &ids)?;
+        self.write_tina_priorities(writer, &ids)
+    }
+
+    /// ```text
+    /// pl p_0 (2)
+    /// pl p_1
+    /// ```
+    fn write_tina_places<T>(&self, writer: &mut T, ids: &NodeIds) -> Result<(), std::io::Error>
+    where
+        T: std::io::Write,
+    {
+        for place in self.places() {
+            let marking = self.place(place).unwrap().marking;
+            let line = if marking > 0 {
+                format!("pl {} ({})\n", ids.place(place), marking)
+            } else {
+                format!("pl {}\n", ids.place(place))
+            };
+            writer.write_all(line.as_bytes())?;
+        }
+        Ok(())
+    }
+
+    /// Read arcs are test arcs (`p?n`) in TINA, inhibitor arcs are written
+    /// as `p?-n`.
+    /// ```text
+    /// tr t_0 [2,5] p_0*2 p_1?1 p_2?-1 -> p_3
+    /// tr t_1 [0,w[ p_3 -> p_0
+    /// ```
+    fn write_tina_transitions<T>(&self, writer: &mut T, ids: &NodeIds) -> Result<(), std::io::Error>
+    where
+        T: std::io::Write,
+    {
+        for transition in self.transitions() {
+            let mut line = format!("tr {}", ids.transition(transition));
+            if let Some(interval) = self.transition(transition).unwrap().interval {
+                line.push(' ');
+                line.push_str(&format_interval(interval));
+            }
+            let (consume, produce) = self.weights(transition).expect("error in weights");
+            for (place, weight) in consume {
+                line.push(' ');
+                line.push_str(&format_weight(ids.place(place), weight));
+            }
+            for (kind, separator) in &[(ArcKind::Read, "?"), (ArcKind::Inhibitor, "?-")] {
+                let arcs = self
+                    .special_weights(transition, *kind)
+                    .expect("error in special arcs");
+                for (place, weight) in arcs {
+                    line.push(' ');
+                    line.push_str(&format!("{}{}{}", ids.place(place), separator, weight));
+                }
+            }
+            line.push_str(" ->");
+            for (place, weight) in produce {
+                line.push(' ');
+                line.push_str(&format_weight(ids.place(place), weight));
+            }
+            line.push('\n');
+            writer.write_all(line.as_bytes())?;
+        }
+        Ok(())
+    }
+
+    /// ```text
+    /// pr t_0 > t_1
+    /// ```
+    fn write_tina_priorities<T>(&self, writer: &mut T, ids: &NodeIds) -> Result<(), std::io::Error>
+    where
+        T: std::io::Write,
+    {
+        for (higher, lower) in self.priorities() {
+            let line = format!(
+                "pr {} > {}\n",
+                ids.transition(higher),
+                ids.transition(lower)
+            );
+            writer.write_all(line.as_bytes())?;
+        }
+        Ok(())
+    }
+}
+
+/// `[a,b]` or `[a,w[` for intervals without upper bound
+fn format_interval(interval: TimeInterval) -> String {
+    match interval.latest {
+        Some(latest) => format!("[{},{}]", interval.earliest, latest),
+        None => format!("[{},w[", interval.earliest),
+    }
+}
+
+/// weights of one are omitted
+fn format_weight(place: &str, weight: usize) -> String {
+    if weight > 1 {
+        format!("{}*{}", place, weight)
+    } else {
+        place.to_string()
+    }
+}
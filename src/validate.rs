@@ -0,0 +1,182 @@
+use crate::{ArcKind, ArcRef, NodeRef, PetriError, PetriNet, Result};
+use std::collections::BTreeMap;
+
+impl PetriNet {
+    /// Checks that the arcs, the pre- and postsets of the nodes and the name
+    /// index agree with each other. The first divergence is reported as
+    /// `CorruptedData`.
+    pub fn validate(&self) -> Result<()> {
+        let mut connections = BTreeMap::new();
+        for arc in self.arcs() {
+            let data = self.arc(arc)?;
+            let describe = || format!("arc_{} ({:?} -> {:?})", arc.index, data.source, data.sink);
+            if self.check_node(data.source).is_err() || self.check_node(data.sink).is_err() {
+                return corrupted(format!("{} connects a removed node", describe()));
+            }
+            if data.mult == 0 {
+                return corrupted(format!("{} has a multiplicity of zero", describe()));
+            }
+            if let Some(other) = connections.insert((data.source, data.sink, data.kind), arc) {
+                return corrupted(format!("{} is parallel to arc_{}", describe(), other.index));
+            }
+            if self.find_arc_of_kind(data.source, data.sink, data.kind) != Some(arc) {
+                return corrupted(format!("{} is missing in a pre- or postset", describe()));
+            }
+            let registered = match (data.source, data.sink, data.kind) {
+                (NodeRef::Place(place), NodeRef::Transition(transition), ArcKind::Normal) => {
+                    self.place(place)?.postset.get(&transition)
+                }
+                (NodeRef::Transition(transition), NodeRef::Place(place), ArcKind::Normal) => {
+                    self.place(place)?.preset.get(&transition)
+                }
+                (NodeRef::Place(place), NodeRef::Transition(transition), kind) => {
+                    self.place(place)?.special.get(&(transition, kind))
+                }
+                _ => return corrupted(format!("{} violates the bipartition", describe())),
+            };
+            if registered != Some(&arc) {
+                return corrupted(format!("{} is missing in a pre- or postset", describe()));
+            }
+        }
+        // every pre- and postset entry has to belong to one of the arcs above
+        let mut entries = 0;
+        for place in self.places() {
+            let data = self.place(place)?;
+            if data
+                .capacity
+                .is_some_and(|capacity| data.marking > capacity)
+            {
+                return corrupted(format!("{:?} holds more tokens than its capacity", place));
+            }
+            for (transition, arc) in &data.preset {
+                self.check_entry(
+                    *arc,
+                    NodeRef::Transition(*transition),
+                    NodeRef::Place(place),
+                    ArcKind::Normal,
+                )?;
+            }
+            for (transition, arc) in &data.postset {
+                self.check_entry(
+                    *arc,
+                    NodeRef::Place(place),
+                    NodeRef::Transition(*transition),
+                    ArcKind::Normal,
+                )?;
+            }
+            for ((transition, kind), arc) in &data.special {
+                self.check_entry(
+                    *arc,
+                    NodeRef::Place(place),
+                    NodeRef::Transition(*transition),
+                    *kind,
+                )?;
+            }
+            entries += data.preset.len() + data.postset.len() + data.special.len();
+        }
+        for transition in self.transitions() {
+            let data = self.transition(transition)?;
+            for (place, arc) in &data.preset {
+                self.check_entry(
+                    *arc,
+                    NodeRef::Place(*place),
+                    NodeRef::Transition(transition),
+                    ArcKind::Normal,
+                )?;
+            }
+            for (place, arc) in &data.postset {
+                self.check_entry(
+                    *arc,
+                    NodeRef::Transition(transition),
+                    NodeRef::Place(*place),
+                    ArcKind::Normal,
+                )?;
+            }
+            for ((place, kind), arc) in &data.special {
+                self.check_entry(
+                    *arc,
+                    NodeRef::Place(*place),
+                    NodeRef::Transition(transition),
+                    *kind,
+                )?;
+            }
+            entries += data.preset.len() + data.postset.len() + data.special.len();
+        }
+        if entries != 2 * connections.len() {
+            return corrupted(format!(
+                "{} pre- and postset entries for {} arcs",
+                entries,
+                connections.len()
+            ));
+        }
+        for (higher, lower) in self.priorities() {
+            if self.transition(higher).is_err() || self.transition(lower).is_err() {
+                return corrupted(format!(
+                    "priority of {:?} over {:?} refers to a removed transition",
+                    higher, lower
+                ));
+            }
+            if self.higher_priorities(higher).contains(&higher) {
+                return corrupted(format!("priorities of {:?} form a cycle", higher));
+            }
+        }
+        self.validate_names()
+    }
+
+    fn check_entry(
+        &self,
+        arc: ArcRef,
+        source: NodeRef,
+        sink: NodeRef,
+        kind: ArcKind,
+    ) -> Result<()> {
+        match self.arc(arc) {
+            Ok(data) if data.source == source && data.sink == sink && data.kind == kind => Ok(()),
+            Ok(_) => corrupted(format!(
+                "arc_{} is registered as {:?} arc between {:?} and {:?} but differs",
+                arc.index, kind, source, sink
+            )),
+            Err(_) => corrupted(format!(
+                "removed arc_{} is registered between {:?} and {:?}",
+                arc.index, source, sink
+            )),
+        }
+    }
+
+    fn validate_names(&self) -> Result<()> {
+        let mut named = 0;
+        let nodes = self
+            .places()
+            .map(|place| (NodeRef::Place(place), self.place(place).map(|p| &p.name)))
+            .chain(self.transitions().map(|transition| {
+                (
+                    NodeRef::Transition(transition),
+                    self.transition(transition).map(|t| &t.name),
+                )
+            }));
+        for (node, name) in nodes {
+            if let Some(name) = name? {
+                named += 1;
+                if !self
+                    .names
+                    .get(name)
+                    .is_some_and(|nodes| nodes.contains(&node))
+                {
+                    return corrupted(format!("{:?} is missing in the name index", node));
+                }
+            }
+        }
+        let indexed: usize = self.names.values().map(Vec::len).sum();
+        if indexed != named {
+            return corrupted(format!(
+                "{} named nodes but {} entries in the name index",
+                named, indexed
+            ));
+        }
+        Ok(())
+    }
+}
+
+fn corrupted(message: String) -> Result<()> {
+    Err(PetriError::CorruptedData(message))
+}
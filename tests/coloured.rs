@@ -0,0 +1,205 @@
+//! Coloured nets, their unfolding and symmetric nets in PNML.
+
+mod common;
+
+use common::philosophers;
+use petri_to_star::{
+    ArcExpression, Colour, ColouredNet, ExplorationLimits, Guard, PetriError, PetriNet, Sort, Term,
+};
+
+#[test]
+fn coloured_unfolding() {
+    let net = philosophers();
+    let unfolded = net.unfold().unwrap();
+    unfolded.validate().unwrap();
+    assert_eq!(unfolded.places().count(), 9);
+    assert_eq!(unfolded.transitions().count(), 9);
+    let lola = unfolded.to_lola_string().unwrap();
+    assert!(lola.contains(
+        "TRANSITION take_2\n  CONSUME\n    think_2 : 1,\n    fork_0 : 1,\n    fork_2 : 1;\n"
+    ));
+    assert!(lola.contains("TRANSITION wave_0_2\n"));
+    assert!(!lola.contains("TRANSITION wave_2_0\n"));
+    // at most one philosopher eats at a time
+    let graph = unfolded
+        .reachability_graph(&ExplorationLimits::default())
+        .unwrap();
+    assert_eq!(graph.markings().len(), 4);
+
+    // zero tokens make no arcs, sizes of sorts are not listed
+    let mut net = ColouredNet::new();
+    let wide = net
+        .add_sort(
+            "wide".into(),
+            Sort::Range {
+                start: 0,
+                end: 999_999,
+            },
+        )
+        .unwrap();
+    let huge = net
+        .add_sort("huge".into(), Sort::Product(vec![wide, wide, wide]))
+        .unwrap();
+    let empty = net
+        .add_sort("empty".into(), Sort::Range { start: 1, end: 0 })
+        .unwrap_err();
+    assert!(matches!(empty, PetriError::InvalidData(_)));
+    assert!(matches!(
+        net.add_sort("overflow".into(), Sort::Product(vec![huge, huge])),
+        Err(PetriError::InvalidData(_))
+    ));
+    let dot = net.add_sort("dot".into(), Sort::Dot).unwrap();
+    let place = net.add_place("place".into(), dot).unwrap();
+    let transition = net.add_transition("transition".into());
+    let nothing = ArcExpression::Scaled(0, Box::new(ArcExpression::All));
+    net.add_input_arc(place, transition, nothing).unwrap();
+    let token = ArcExpression::Term(Term::Constant(Colour::Dot));
+    let none = ArcExpression::Scaled(0, Box::new(token));
+    net.add_output_arc(transition, place, none).unwrap();
+    let unfolded = net.unfold().unwrap();
+    assert_eq!(unfolded.transitions().count(), 1);
+    assert_eq!(unfolded.arcs().count(), 0);
+}
+
+#[test]
+fn symmetric_nets() {
+    let net = philosophers();
+    let pnml = net.to_pnml_string().unwrap();
+    let imported = ColouredNet::from_pnml_string(&pnml).unwrap();
+    assert_eq!(imported.to_pnml_string().unwrap(), pnml);
+    assert_eq!(
+        PetriNet::from_pnml_string(&pnml)
+            .unwrap()
+            .to_lola_string()
+            .unwrap(),
+        net.unfold().unwrap().to_lola_string().unwrap()
+    );
+
+    // declarations after the page as in the models of the model checking contest
+    let document = r#"<?xml version="1.0"?>
+<pnml xmlns="http://www.pnml.org/version-2009/grammar/pnml">
+  <net id="pairs" type="http://www.pnml.org/version-2009/grammar/symmetricnet">
+    <page id="page">
+      <place id="source">
+        <type><structure><usersort declaration="colour"/></structure></type>
+        <hlinitialMarking><structure>
+          <all><usersort declaration="colour"/></all>
+        </structure></hlinitialMarking>
+      </place>
+      <place id="target">
+        <type><structure><usersort declaration="pair"/></structure></type>
+      </place>
+      <place id="go">
+        <type><structure><dot/></structure></type>
+        <hlinitialMarking><structure>
+          <numberof>
+            <subterm><numberconstant value="1"><positive/></numberconstant></subterm>
+            <subterm><dotconstant/></subterm>
+          </numberof>
+        </structure></hlinitialMarking>
+      </place>
+      <transition id="combine">
+        <condition><structure>
+          <imply>
+            <subterm><equality>
+              <subterm><variable refvariable="c"/></subterm>
+              <subterm><useroperator declaration="red"/></subterm>
+            </equality></subterm>
+            <subterm><inequality>
+              <subterm><variable refvariable="d"/></subterm>
+              <subterm><useroperator declaration="red"/></subterm>
+            </inequality></subterm>
+          </imply>
+        </structure></condition>
+      </transition>
+      <arc id="a0" source="source" target="combine">
+        <hlinscription><structure><add>
+          <subterm><variable refvariable="c"/></subterm>
+          <subterm><variable refvariable="d"/></subterm>
+        </add></structure></hlinscription>
+      </arc>
+      <arc id="a1" source="combine" target="target">
+        <hlinscription><structure><tuple>
+          <subterm><variable refvariable="c"/></subterm>
+          <subterm><variable refvariable="d"/></subterm>
+        </tuple></structure></hlinscription>
+      </arc>
+      <arc id="a2" source="go" target="combine"/>
+      <arc id="a3" source="combine" target="go"/>
+    </page>
+    <declaration><structure><declarations>
+      <namedsort id="pair" name="Pair">
+        <productsort>
+          <usersort declaration="colour"/>
+          <usersort declaration="colour"/>
+        </productsort>
+      </namedsort>
+      <namedsort id="colour" name="Colour">
+        <finiteenumeration>
+          <feconstant id="red" name="red"/>
+          <feconstant id="blue" name="blue"/>
+        </finiteenumeration>
+      </namedsort>
+      <variabledecl id="c" name="c"><usersort declaration="colour"/></variabledecl>
+      <variabledecl id="d" name="d"><usersort declaration="colour"/></variabledecl>
+    </declarations></structure></declaration>
+  </net>
+</pnml>"#;
+    let net = ColouredNet::from_pnml_string(document).unwrap();
+    let unfolded = net.unfold().unwrap();
+    assert_eq!(unfolded.places().count(), 7);
+    assert_eq!(unfolded.transitions().count(), 3);
+    let lola = unfolded.to_lola_string().unwrap();
+    assert!(lola.contains(
+        "TRANSITION combine_blue_blue\n  CONSUME\n    source_blue : 2,\n    go_dot : 1;\n  PRODUCE\n    target_blue_blue : 1,\n    go_dot : 1;\n"
+    ));
+    assert!(!lola.contains("combine_red_red"));
+    let imported = ColouredNet::from_pnml_string(&net.to_pnml_string().unwrap()).unwrap();
+    assert_eq!(imported.unfold().unwrap().to_lola_string().unwrap(), lola);
+}
+
+#[test]
+fn constant_comparisons() {
+    let build = |guard: Guard, reversed: bool| {
+        let mut net = ColouredNet::new();
+        let level = net
+            .add_sort(
+                "level".into(),
+                Sort::Enumeration(vec!["low".into(), "high".into()]),
+            )
+            .unwrap();
+        if reversed {
+            net.add_sort(
+                "reversed".into(),
+                Sort::Enumeration(vec!["high".into(), "low".into()]),
+            )
+            .unwrap();
+        }
+        let place = net.add_place("place".into(), level).unwrap();
+        let transition = net.add_transition("transition".into());
+        transition.guard(&mut net, guard).unwrap();
+        let low = Term::Constant(Colour::Constant("low".into()));
+        net.add_output_arc(transition, place, ArcExpression::Term(low))
+            .unwrap();
+        net.unfold()
+    };
+    let constant = |name: &str| Term::Constant(Colour::Constant(name.into()));
+    // the declaration order counts, not the alphabetical one
+    let ascending = Guard::LessThan(constant("low"), constant("high"));
+    let unfolded = build(ascending.clone(), false).unwrap();
+    assert_eq!(unfolded.transitions().count(), 1);
+    let descending = Guard::GreaterThan(constant("low"), constant("high"));
+    assert_eq!(build(descending, false).unwrap().transitions().count(), 0);
+    assert!(matches!(
+        build(ascending, true),
+        Err(PetriError::InvalidData(_))
+    ));
+    // equality needs no order
+    let equal = Guard::Equal(constant("high"), constant("high"));
+    assert_eq!(build(equal, true).unwrap().transitions().count(), 1);
+    let foreign = Guard::LessThan(constant("low"), Term::Constant(Colour::Integer(1)));
+    assert!(matches!(
+        build(foreign, false),
+        Err(PetriError::InvalidData(_))
+    ));
+}
@@ -0,0 +1,98 @@
+//! Nets shared by the integration tests.
+
+#![allow(dead_code)]
+
+use petri_to_star::{ArcExpression, Colour, ColouredNet, Guard, PetriNet, PlaceRef, Sort, Term};
+use std::convert::TryFrom;
+
+/// two processes competing for a shared resource
+pub fn mutex() -> PetriNet {
+    build_mutex(false)
+}
+
+/// Arcs that are added in a different order lead to different arc ids in
+/// PNML and DOT, but the pre- and postsets stay the same.
+pub fn build_mutex(resource_first: bool) -> PetriNet {
+    let mut net = PetriNet::new();
+    let resource = net.add_place();
+    resource.name(&mut net, "resource".into()).unwrap();
+    PlaceRef::try_from(resource)
+        .unwrap()
+        .marking(&mut net, 1)
+        .unwrap();
+    for process in 0..2 {
+        let idle = net.add_place();
+        let critical = net.add_place();
+        let enter = net.add_transition();
+        let leave = net.add_transition();
+        idle.name(&mut net, format!("idle_{}", process)).unwrap();
+        critical
+            .name(&mut net, format!("critical_{}", process))
+            .unwrap();
+        enter.name(&mut net, format!("enter_{}", process)).unwrap();
+        leave.name(&mut net, format!("leave_{}", process)).unwrap();
+        PlaceRef::try_from(idle)
+            .unwrap()
+            .marking(&mut net, 1)
+            .unwrap();
+        let mut arcs = vec![
+            (idle, enter),
+            (resource, enter),
+            (enter, critical),
+            (critical, leave),
+            (leave, idle),
+            (leave, resource),
+        ];
+        if resource_first {
+            arcs.reverse();
+        }
+        for (source, sink) in arcs {
+            net.add_arc(source, sink).unwrap();
+        }
+    }
+    net
+}
+
+/// three dining philosophers, `wave` has a guard over two variables
+pub fn philosophers() -> ColouredNet {
+    let mut net = ColouredNet::new();
+    let philosophers = net
+        .add_sort("philosopher".into(), Sort::Range { start: 0, end: 2 })
+        .unwrap();
+    let x = net.add_variable("x".into(), philosophers).unwrap();
+    let y = net.add_variable("y".into(), philosophers).unwrap();
+    let think = net.add_place("think".into(), philosophers).unwrap();
+    let fork = net.add_place("fork".into(), philosophers).unwrap();
+    let eat = net.add_place("eat".into(), philosophers).unwrap();
+    let all: Vec<_> = (0..3).map(|i| (Colour::Integer(i), 1)).collect();
+    think.marking(&mut net, all.clone()).unwrap();
+    fork.marking(&mut net, all).unwrap();
+    let forks = ArcExpression::Sum(vec![
+        ArcExpression::Term(Term::Variable(x)),
+        ArcExpression::Term(Term::Successor(Box::new(Term::Variable(x)))),
+    ]);
+    let take = net.add_transition("take".into());
+    net.add_input_arc(think, take, ArcExpression::Term(Term::Variable(x)))
+        .unwrap();
+    net.add_input_arc(fork, take, forks.clone()).unwrap();
+    net.add_output_arc(take, eat, ArcExpression::Term(Term::Variable(x)))
+        .unwrap();
+    let release = net.add_transition("release".into());
+    net.add_input_arc(eat, release, ArcExpression::Term(Term::Variable(x)))
+        .unwrap();
+    net.add_output_arc(release, think, ArcExpression::Term(Term::Variable(x)))
+        .unwrap();
+    net.add_output_arc(release, fork, forks).unwrap();
+    // only binds pairs of different philosophers in ascending order
+    let wave = net.add_transition("wave".into());
+    wave.guard(
+        &mut net,
+        Guard::LessThan(Term::Variable(x), Term::Variable(y)),
+    )
+    .unwrap();
+    net.add_input_arc(think, wave, ArcExpression::Term(Term::Variable(y)))
+        .unwrap();
+    net.add_output_arc(wave, think, ArcExpression::Term(Term::Variable(y)))
+        .unwrap();
+    net
+}
@@ -0,0 +1,215 @@
+//! Import and export of the file formats, checked on selected parts of the
+//! output and by reading it back.
+
+mod common;
+
+use common::mutex;
+use petri_to_star::{NodeRef, PetriError, PetriNet};
+
+#[test]
+fn exporters_agree_on_weights() {
+    let mut net = PetriNet::new();
+    let place = net.add_place();
+    let transition = net.add_transition();
+    let arc = net.add_arc(place, transition).unwrap();
+    arc.multiplicity(&mut net, 2).unwrap();
+    // a parallel arc is merged into the existing one
+    assert_eq!(net.add_weighted_arc(place, transition, 3).unwrap(), arc);
+    net.add_weighted_arc(transition, place, 4).unwrap();
+    net.validate().unwrap();
+    let lola = net.to_lola_string().unwrap();
+    assert!(lola.contains("CONSUME\n    p_0 : 5;"));
+    assert!(lola.contains("PRODUCE\n    p_0 : 4;"));
+    let pnml = net.to_pnml_string().unwrap();
+    assert!(pnml.contains("<text>5</text>"));
+    assert!(pnml.contains("<text>4</text>"));
+    let dot = net.to_dot_string().unwrap();
+    assert!(dot.contains("label=\"5\""));
+    assert!(dot.contains("label=\"4\""));
+}
+
+#[test]
+fn inhibitor_arcs() {
+    let mut net = PetriNet::new();
+    let place = net.add_place();
+    let transition = net.add_transition();
+    net.add_inhibitor_arc(place, transition, 2).unwrap();
+    // parallel inhibitor arcs keep the lower threshold
+    net.add_inhibitor_arc(place, transition, 3).unwrap();
+    net.validate().unwrap();
+    let dot = net.to_dot_string().unwrap();
+    assert!(dot.contains("p_0 -> t_0 [arrowhead=\"odot\" label=\"2\"];"));
+    let pnml = net.to_pnml_string().unwrap();
+    assert!(pnml.contains("<arcType>inhibitor</arcType>"));
+    let imported = PetriNet::from_pnml_string(&pnml).unwrap();
+    assert_eq!(imported.to_pnml_string().unwrap(), pnml);
+    let error = net.to_lola_string().unwrap_err();
+    assert_eq!(error.kind(), std::io::ErrorKind::InvalidInput);
+}
+
+#[test]
+fn read_and_reset_arcs() {
+    let mut net = PetriNet::new();
+    let variable = net.add_place();
+    let buffer = net.add_place();
+    let transition = net.add_transition();
+    net.add_read_arc(variable, transition, 2).unwrap();
+    net.add_arc(transition, variable).unwrap();
+    net.validate().unwrap();
+    let lola = net.to_lola_string().unwrap();
+    assert!(lola.contains("CONSUME\n    p_0 : 2;"));
+    assert!(lola.contains("PRODUCE\n    p_0 : 3;"));
+    let dot = net.to_dot_string().unwrap();
+    assert!(dot.contains("p_0 -> t_0 [style=\"dashed\" arrowhead=\"none\" label=\"2\"];"));
+
+    let reset = net.add_reset_arc(buffer, transition).unwrap();
+    let dot = net.to_dot_string().unwrap();
+    assert!(dot.contains("p_1 -> t_0 [arrowhead=\"normalnormal\"];"));
+    let pnml = net.to_pnml_string().unwrap();
+    let imported = PetriNet::from_pnml_string(&pnml).unwrap();
+    assert_eq!(imported.to_pnml_string().unwrap(), pnml);
+    // reset arcs have no weight
+    assert!(matches!(
+        reset.multiplicity(&mut net, 2),
+        Err(PetriError::InvalidData(_))
+    ));
+    let (head, tail) = pnml.split_at(pnml.find("id=\"arc_2\"").unwrap());
+    let weighted = head.to_string() + &tail.replacen("<text>1</text>", "<text>2</text>", 1);
+    assert!(matches!(
+        PetriNet::from_pnml_string(&weighted),
+        Err(PetriError::CorruptedData(_))
+    ));
+    let error = net.to_lola_string().unwrap_err();
+    assert_eq!(error.kind(), std::io::ErrorKind::InvalidInput);
+}
+
+/// same places, transitions, arcs, weights and initial marking
+fn assert_equivalent(net: &PetriNet, other: &PetriNet) {
+    assert_eq!(
+        net.places().collect::<Vec<_>>(),
+        other.places().collect::<Vec<_>>()
+    );
+    assert_eq!(
+        net.transitions().collect::<Vec<_>>(),
+        other.transitions().collect::<Vec<_>>()
+    );
+    assert_eq!(net.arcs().count(), other.arcs().count());
+    assert_eq!(net.initial_marking(), other.initial_marking());
+    for transition in net.transitions() {
+        assert_eq!(
+            transition.preset(net).unwrap(),
+            transition.preset(other).unwrap()
+        );
+        assert_eq!(
+            transition.postset(net).unwrap(),
+            transition.postset(other).unwrap()
+        );
+    }
+}
+
+#[test]
+fn pnml_round_trip() {
+    let mut weighted = mutex();
+    let resource = weighted.place_by_name("resource").unwrap();
+    resource.marking(&mut weighted, 3).unwrap();
+    let enter = weighted.node_by_name("enter_0").unwrap();
+    let arc = weighted.find_arc(NodeRef::Place(resource), enter).unwrap();
+    arc.multiplicity(&mut weighted, 2).unwrap();
+    let fixture = PetriNet::from_pnml_string(include_str!("snapshots/mutex.pnml")).unwrap();
+    for net in &[mutex(), weighted, fixture] {
+        let imported = PetriNet::from_pnml_string(&net.to_pnml_string().unwrap()).unwrap();
+        assert_equivalent(net, &imported);
+        for name in &["resource", "idle_1", "enter_0", "leave_1"] {
+            assert_eq!(net.node_by_name(name), imported.node_by_name(name));
+        }
+    }
+    assert_equivalent(
+        &mutex(),
+        &PetriNet::from_pnml_string(include_str!("snapshots/mutex.pnml")).unwrap(),
+    );
+}
+
+#[test]
+fn malformed_pnml() {
+    let namespace = "xmlns=\"http://www.pnml.org/version-2009/grammar/pnml\"";
+    let no_net = format!("<pnml {}></pnml>", namespace);
+    assert!(matches!(
+        PetriNet::from_pnml_string(&no_net),
+        Err(PetriError::NetNotFound)
+    ));
+    let no_page = format!(
+        "<pnml {}><net id=\"net\" type=\"ptnet\"/></pnml>",
+        namespace
+    );
+    assert!(matches!(
+        PetriNet::from_pnml_string(&no_page),
+        Err(PetriError::PageNotFound)
+    ));
+    let dangling = format!(
+        "<pnml {}><net id=\"net\" type=\"ptnet\"><page id=\"page\">\
+         <place id=\"p\"/><arc id=\"a\" source=\"p\" target=\"t\"/>\
+         </page></net></pnml>",
+        namespace
+    );
+    assert!(matches!(
+        PetriNet::from_pnml_string(&dangling),
+        Err(PetriError::CorruptedData(_))
+    ));
+}
+
+#[test]
+fn lola_parsing() {
+    let document = "{ buffer with two slots }
+PLACE
+    SAFE 2: buffer/* inline */, spare;
+    SAFE: flag;
+    source; // comment until the end of the line
+MARKING
+    source : 3, flag;
+TRANSITION produce WEAK FAIR
+  CONSUME source : 2;
+  PRODUCE buffer : 2, flag, spare;
+TRANSITION consume
+  CONSUME buffer, buffer, flag : 1;
+  PRODUCE ;
+";
+    let net = PetriNet::from_lola(document.as_bytes()).unwrap();
+    assert_eq!(net.places().count(), 4);
+    let source = net.place_by_name("source").unwrap();
+    let buffer = net.place_by_name("buffer").unwrap();
+    let flag = net.place_by_name("flag").unwrap();
+    let initial = net.initial_marking();
+    assert_eq!(initial.tokens(source), 3);
+    assert_eq!(initial.tokens(flag), 1);
+    let produce = net.transition_by_name("produce").unwrap();
+    assert_eq!(produce.preset(&net).unwrap()[&source], 2);
+    assert_eq!(produce.postset(&net).unwrap()[&buffer], 2);
+    // repeated places are accumulated
+    let consume = net.transition_by_name("consume").unwrap();
+    assert_eq!(consume.preset(&net).unwrap()[&buffer], 2);
+    assert!(consume.postset(&net).unwrap().is_empty());
+    let mut exported = net.clone();
+    exported.set_name_ids(true);
+    let lola = exported.to_lola_string().unwrap();
+    assert!(lola.starts_with(
+        "PLACE\n    source;\n  SAFE 1:\n    flag;\n  SAFE 2:\n    buffer,\n    spare;\n"
+    ));
+}
+
+#[test]
+fn lola_parse_errors() {
+    let position = |document: &str| match PetriNet::from_lola_string(document) {
+        Err(PetriError::ParseError { line, column, .. }) => (line, column),
+        result => panic!("unexpected result {:?}", result.map(|_| ())),
+    };
+    assert_eq!(position("PLACE p;\nMARKING q : 1;"), (2, 9));
+    assert_eq!(position("PLACE p, p;"), (1, 10));
+    assert_eq!(position("PLACE p\nMARKING;"), (2, 1));
+    assert_eq!(position("PLACE p;\nMARKING p : ;"), (2, 13));
+    assert_eq!(position("PLACE p;\n  { open\nMARKING;"), (2, 3));
+    assert_eq!(position("PLACE p;\nMARKING;\nTRANSITION CONSUME"), (3, 12));
+    // comments end identifiers
+    assert_eq!(position("PLACE p;\nMARKING p// comment\n: ;"), (3, 3));
+    // the capacity is exceeded by the last occurrence of the place
+    assert_eq!(position("PLACE SAFE 2: p;\nMARKING p : 1, p : 2;"), (2, 16));
+}
@@ -0,0 +1,256 @@
+//! CTL and LTL model checking and the property specifications.
+
+mod common;
+
+use common::mutex;
+use petri_to_star::{
+    Atom, Comparison, Ctl, ExplorationLimits, Lasso, Ltl, NodeRef, PetriError, PetriNet, PlaceRef,
+    Property, TransitionRef, Verdict, Witness,
+};
+use std::convert::TryFrom;
+
+#[test]
+fn ctl() {
+    let net = mutex();
+    let limits = ExplorationLimits::default();
+    let critical = |name: &str, comparison, tokens| {
+        Ctl::Atom(Atom::Tokens(
+            net.place_by_name(name).unwrap(),
+            comparison,
+            tokens,
+        ))
+    };
+    let enter_0 = net.transition_by_name("enter_0").unwrap();
+    let leave_0 = net.transition_by_name("leave_0").unwrap();
+    let enter_1 = net.transition_by_name("enter_1").unwrap();
+
+    let exclusion = Ctl::AllGlobally(Box::new(Ctl::Not(Box::new(Ctl::And(vec![
+        critical("critical_0", Comparison::GreaterOrEqual, 1),
+        critical("critical_1", Comparison::GreaterOrEqual, 1),
+    ])))));
+    let verdict = net.check_ctl(&exclusion, &limits).unwrap();
+    assert!(verdict.holds);
+    assert_eq!(verdict.witness, None);
+
+    let reset = Ctl::AllGlobally(Box::new(Ctl::ExistsFinally(Box::new(Ctl::Atom(
+        Atom::Fireable(enter_1),
+    )))));
+    assert!(net.check_ctl(&reset, &limits).unwrap().holds);
+
+    // a counterexample to AG is a path to a violating marking
+    let never = Ctl::AllGlobally(Box::new(critical("critical_0", Comparison::Equal, 0)));
+    let verdict = net.check_ctl(&never, &limits).unwrap();
+    assert!(!verdict.holds);
+    match verdict.witness {
+        Some(Witness::Path(path)) => assert_eq!(path.transitions(), &[enter_0]),
+        other => panic!("unexpected witness {:?}", other),
+    }
+
+    // process 1 can starve, the counterexample to AF loops forever
+    let starvation = Lasso {
+        prefix: Vec::new(),
+        cycle: vec![enter_0, leave_0],
+    };
+    let eventually = Ctl::AllFinally(Box::new(critical(
+        "critical_1",
+        Comparison::GreaterOrEqual,
+        1,
+    )));
+    let verdict = net.check_ctl(&eventually, &limits).unwrap();
+    assert!(!verdict.holds);
+    assert_eq!(verdict.witness, Some(Witness::Lasso(starvation.clone())));
+    let avoid = Ctl::ExistsGlobally(Box::new(critical("critical_1", Comparison::Equal, 0)));
+    let verdict = net.check_ctl(&avoid, &limits).unwrap();
+    assert!(verdict.holds);
+    assert_eq!(verdict.witness, Some(Witness::Lasso(starvation)));
+
+    let small = ExplorationLimits {
+        max_states: Some(1),
+        ..ExplorationLimits::default()
+    };
+    assert!(matches!(
+        net.check_ctl(&exclusion, &small),
+        Err(PetriError::LimitsExceeded)
+    ));
+}
+
+#[test]
+fn ltl() {
+    let net = mutex();
+    let limits = ExplorationLimits::default();
+    let critical = |name: &str| {
+        Ltl::Atom(Atom::Tokens(
+            net.place_by_name(name).unwrap(),
+            Comparison::GreaterOrEqual,
+            1,
+        ))
+    };
+    let enter_0 = net.transition_by_name("enter_0").unwrap();
+    let enter_1 = net.transition_by_name("enter_1").unwrap();
+    let leave_0 = net.transition_by_name("leave_0").unwrap();
+
+    let exclusion = Ltl::Globally(Box::new(Ltl::Not(Box::new(Ltl::And(vec![
+        critical("critical_0"),
+        critical("critical_1"),
+    ])))));
+    assert_eq!(
+        net.check_ltl(&exclusion, &limits).unwrap(),
+        Verdict {
+            holds: true,
+            witness: None
+        }
+    );
+    // leaving always follows entering
+    let response = Ltl::Globally(Box::new(Ltl::Or(vec![
+        Ltl::Not(Box::new(Ltl::Fired(enter_0))),
+        Ltl::Next(Box::new(Ltl::Fired(leave_0))),
+    ])));
+    assert!(net.check_ltl(&response, &limits).unwrap().holds);
+
+    // process 1 starves if process 0 keeps the resource busy
+    let fairness = Ltl::Globally(Box::new(Ltl::Finally(Box::new(Ltl::Fired(enter_1)))));
+    let lasso = match net.check_ltl(&fairness, &limits).unwrap() {
+        Verdict {
+            holds: false,
+            witness: Some(Witness::Lasso(lasso)),
+        } => lasso,
+        verdict => panic!("unexpected verdict {:?}", verdict),
+    };
+    assert!(!lasso.cycle.is_empty());
+    assert!(!lasso.cycle.contains(&enter_1));
+    let mut marking = net.initial_marking();
+    for transition in &lasso.prefix {
+        transition.fire(&net, &mut marking).unwrap();
+    }
+    let start = marking.clone();
+    for transition in &lasso.cycle {
+        transition.fire(&net, &mut marking).unwrap();
+    }
+    assert_eq!(marking, start);
+
+    // the limits are shared with the CTL checker
+    let small = ExplorationLimits {
+        max_states: Some(1),
+        ..ExplorationLimits::default()
+    };
+    assert!(matches!(
+        net.check_ltl(&exclusion, &small),
+        Err(PetriError::LimitsExceeded)
+    ));
+}
+
+#[test]
+fn ltl_deadlocks() {
+    // `work` can fire twice, `quit` ends the run at once
+    let mut net = PetriNet::new();
+    let fuel = net.add_place();
+    let running = net.add_place();
+    let work = net.add_transition();
+    let quit = net.add_transition();
+    net.add_arc(fuel, work).unwrap();
+    net.add_arc(running, work).unwrap();
+    net.add_arc(work, running).unwrap();
+    net.add_arc(running, quit).unwrap();
+    let fuel = PlaceRef::try_from(fuel).unwrap();
+    PlaceRef::try_from(running)
+        .unwrap()
+        .marking(&mut net, 1)
+        .unwrap();
+    fuel.marking(&mut net, 2).unwrap();
+    let [work, quit] = [work, quit].map(|transition| TransitionRef::try_from(transition).unwrap());
+    let limits = ExplorationLimits::default();
+    let deadlock = || Ltl::Atom(Atom::Deadlock);
+
+    // every run ends in a dead marking and stutters there
+    let finally = Ltl::Finally(Box::new(Ltl::Globally(Box::new(deadlock()))));
+    assert!(net.check_ltl(&finally, &limits).unwrap().holds);
+    let stuck = Ltl::Globally(Box::new(Ltl::Or(vec![
+        Ltl::Not(Box::new(deadlock())),
+        Ltl::Next(Box::new(deadlock())),
+    ])));
+    assert!(net.check_ltl(&stuck, &limits).unwrap().holds);
+    // no transition fires while the run stutters
+    let stutter = Ltl::Globally(Box::new(Ltl::Or(vec![
+        Ltl::Not(Box::new(deadlock())),
+        Ltl::Next(Box::new(Ltl::Not(Box::new(Ltl::Or(vec![
+            Ltl::Fired(work),
+            Ltl::Fired(quit),
+        ]))))),
+    ])));
+    assert!(net.check_ltl(&stutter, &limits).unwrap().holds);
+
+    // every counterexample ends with `quit` in the dead marking
+    let keeps_working = Ltl::Globally(Box::new(Ltl::Not(Box::new(deadlock()))));
+    let verdict = net.check_ltl(&keeps_working, &limits).unwrap();
+    assert!(!verdict.holds);
+    let lasso = match verdict.witness {
+        Some(Witness::Lasso(lasso)) => lasso,
+        witness => panic!("unexpected witness {:?}", witness),
+    };
+    assert!(lasso.cycle.is_empty());
+    let mut marking = net.initial_marking();
+    for transition in &lasso.prefix {
+        transition.fire(&net, &mut marking).unwrap();
+    }
+    assert_eq!(net.enabled_transitions(&marking).count(), 0);
+    assert_eq!(lasso.prefix.last(), Some(&quit));
+}
+
+#[test]
+fn lola_formulas() {
+    let mut net = mutex();
+    let critical_0 = net.place_by_name("critical_0").unwrap();
+    let critical_1 = net.place_by_name("critical_1").unwrap();
+    let enter_1 = net.transition_by_name("enter_1").unwrap();
+    let both = Ctl::And(vec![
+        Ctl::Atom(Atom::Tokens(critical_0, Comparison::GreaterOrEqual, 1)),
+        Ctl::Atom(Atom::Tokens(critical_1, Comparison::GreaterOrEqual, 1)),
+    ]);
+    let exclusion = Property::Invariant(Ctl::Not(Box::new(both.clone())));
+    assert_eq!(
+        net.to_lola_formula_string(&exclusion).unwrap(),
+        "AG NOT (p_2 >= 1 AND p_4 >= 1)\n"
+    );
+    let limits = ExplorationLimits::default();
+    assert!(net.check_property(&exclusion, &limits).unwrap().holds);
+    assert!(
+        !net.check_property(&Property::Reachable(both), &limits)
+            .unwrap()
+            .holds
+    );
+
+    let progress = Property::Ctl(Ctl::AllGlobally(Box::new(Ctl::ExistsUntil(
+        Box::new(Ctl::Not(Box::new(Ctl::Atom(Atom::Deadlock)))),
+        Box::new(Ctl::Atom(Atom::Fireable(enter_1))),
+    ))));
+    let fairness = Property::Ltl(Ltl::Globally(Box::new(Ltl::Finally(Box::new(Ltl::Atom(
+        Atom::Tokens(critical_1, Comparison::Equal, 0),
+    ))))));
+    assert_eq!(
+        net.to_lola_formula_string(&progress).unwrap(),
+        "AG E (NOT DEADLOCK U FIREABLE(t_2))\n"
+    );
+    assert_eq!(
+        net.to_lola_formula_string(&fairness).unwrap(),
+        "A G F (p_4 = 0)\n"
+    );
+
+    // identifiers agree with the net, names that are keywords are not used
+    net.set_name_ids(true);
+    net.transition_by_name("enter_1")
+        .map(NodeRef::Transition)
+        .unwrap()
+        .name(&mut net, "AG".into())
+        .unwrap();
+    assert!(net.to_lola_string().unwrap().contains("TRANSITION t_2\n"));
+    assert_eq!(
+        net.to_lola_formula_string(&progress).unwrap(),
+        "AG E (NOT DEADLOCK U FIREABLE(t_2))\n"
+    );
+    assert_eq!(
+        net.to_lola_formula_string(&fairness).unwrap(),
+        "A G F (critical_1 = 0)\n"
+    );
+    let fired = Property::Ltl(Ltl::Finally(Box::new(Ltl::Fired(enter_1))));
+    assert!(net.to_lola_formula_string(&fired).is_err());
+}
@@ -0,0 +1,309 @@
+//! The net model: arcs, capacities, priorities, names and the firing rule.
+
+mod common;
+
+use common::mutex;
+use petri_to_star::{ExplorationLimits, NodeRef, PetriError, PetriNet, PlaceRef, TransitionRef};
+use std::convert::TryFrom;
+
+#[test]
+fn token_game() {
+    let mut net = PetriNet::new();
+    let input = net.add_place();
+    let guard = net.add_place();
+    let blocker = net.add_place();
+    let trash = net.add_place();
+    let output = net.add_place();
+    let transition = net.add_transition();
+    net.add_weighted_arc(input, transition, 2).unwrap();
+    net.add_read_arc(guard, transition, 1).unwrap();
+    net.add_inhibitor_arc(blocker, transition, 1).unwrap();
+    net.add_reset_arc(trash, transition).unwrap();
+    net.add_weighted_arc(transition, output, 3).unwrap();
+    let [input, guard, blocker, trash, output] =
+        [input, guard, blocker, trash, output].map(|place| PlaceRef::try_from(place).unwrap());
+    let transition = TransitionRef::try_from(transition).unwrap();
+    input.marking(&mut net, 3).unwrap();
+    trash.marking(&mut net, 5).unwrap();
+
+    // the read arc lacks a token
+    let mut marking = net.initial_marking();
+    assert!(!transition.is_enabled(&net, &marking).unwrap());
+    assert!(matches!(
+        transition.fire(&net, &mut marking),
+        Err(PetriError::TransitionNotEnabled)
+    ));
+    assert_eq!(marking, net.initial_marking());
+    marking.set_tokens(guard, 1).unwrap();
+    assert!(transition.is_enabled(&net, &marking).unwrap());
+    // the inhibitor arc blocks
+    marking.set_tokens(blocker, 1).unwrap();
+    assert!(!transition.is_enabled(&net, &marking).unwrap());
+    marking.set_tokens(blocker, 0).unwrap();
+
+    transition.fire(&net, &mut marking).unwrap();
+    assert_eq!(marking.tokens(input), 1);
+    assert_eq!(marking.tokens(guard), 1);
+    assert_eq!(marking.tokens(trash), 0);
+    assert_eq!(marking.tokens(output), 3);
+    assert!(!transition.is_enabled(&net, &marking).unwrap());
+
+    // strict firing rule: the output place would exceed its capacity
+    output.capacity(&mut net, Some(5)).unwrap();
+    marking.set_tokens(input, 2).unwrap();
+    assert!(!transition.is_enabled(&net, &marking).unwrap());
+    output.capacity(&mut net, Some(6)).unwrap();
+    assert!(transition.is_enabled(&net, &marking).unwrap());
+
+    // markings of the net before a place was added do not fit anymore
+    let added = PlaceRef::try_from(net.add_place()).unwrap();
+    assert!(matches!(
+        marking.set_tokens(added, 1),
+        Err(PetriError::PlaceNotFound)
+    ));
+    assert!(transition.is_enabled(&net, &marking).is_err());
+    let mut current = net.initial_marking();
+    current.set_tokens(added, 1).unwrap();
+    current.set_tokens(added, 0).unwrap();
+    assert_eq!(current, net.initial_marking());
+}
+
+#[test]
+fn removal() {
+    let mut net = mutex();
+    let critical = net.place_by_name("critical_1").unwrap();
+    let leave = net.transition_by_name("leave_1").unwrap();
+    let enter = net.transition_by_name("enter_1").unwrap();
+    let first_arc = net.arcs().next().unwrap();
+    net.remove_arc(first_arc).unwrap();
+    net.remove_place(critical).unwrap();
+    net.remove_transition(leave).unwrap();
+    // the connected arcs went with the nodes
+    assert_eq!(net.arcs().count(), 7);
+    assert_eq!(net.place_by_name("critical_1"), None);
+    assert_eq!(net.transition_by_name("leave_1"), None);
+
+    assert!(matches!(
+        net.remove_place(critical),
+        Err(PetriError::PlaceNotFound)
+    ));
+    assert!(matches!(
+        net.remove_transition(leave),
+        Err(PetriError::TransitionNotFound)
+    ));
+    assert!(matches!(
+        net.remove_arc(first_arc),
+        Err(PetriError::ArcNotFound)
+    ));
+    assert!(matches!(
+        critical.marking(&mut net, 1),
+        Err(PetriError::PlaceNotFound)
+    ));
+    assert!(matches!(
+        net.add_arc(NodeRef::Place(critical), NodeRef::Transition(enter)),
+        Err(PetriError::PlaceNotFound)
+    ));
+
+    // slots of removed nodes are never handed out again
+    let added = PlaceRef::try_from(net.add_place()).unwrap();
+    assert_ne!(added, critical);
+    assert_eq!(net.places().count(), 5);
+    assert!(matches!(
+        net.remove_place(critical),
+        Err(PetriError::PlaceNotFound)
+    ));
+
+    let dot = net.to_dot_string().unwrap();
+    assert!(dot.contains("p_5 ") && !dot.contains("p_4") && !dot.contains("t_3"));
+    let lola = net.to_lola_string().unwrap();
+    assert!(lola.contains("p_5;") && !lola.contains("p_4") && !lola.contains("t_3"));
+    let pnml = net.to_pnml_string().unwrap();
+    assert!(pnml.contains("place_5"));
+    for removed in &[
+        "place_4",
+        "transition_3",
+        "\"arc_0\"",
+        "critical_1",
+        "leave_1",
+    ] {
+        assert!(!pnml.contains(removed), "{} was exported", removed);
+    }
+    assert_eq!(
+        net.incidence_matrix().unwrap().to_csv_string().unwrap(),
+        ",t_0,t_1,t_2\np_0,-1,1,-1\np_1,0,1,0\np_2,1,-1,0\np_3,0,0,-1\np_5,0,0,0\n"
+    );
+}
+
+#[test]
+fn capacities() {
+    let mut net = PetriNet::new();
+    let source = net.add_place();
+    let buffer = net.add_place();
+    let produce = net.add_transition();
+    let consume = net.add_transition();
+    source.name(&mut net, "source".into()).unwrap();
+    buffer.name(&mut net, "buffer".into()).unwrap();
+    net.set_name_ids(true);
+    net.add_arc(produce, source).unwrap();
+    net.add_arc(source, produce).unwrap();
+    net.add_weighted_arc(produce, buffer, 2).unwrap();
+    net.add_arc(buffer, consume).unwrap();
+    let source = PlaceRef::try_from(source).unwrap();
+    let buffer = PlaceRef::try_from(buffer).unwrap();
+    source.marking(&mut net, 1).unwrap();
+    buffer.capacity(&mut net, Some(3)).unwrap();
+    let lola = net.to_lola_string().unwrap();
+    assert!(lola.starts_with("PLACE\n    source;\n  SAFE 3:\n    buffer;\n\n"));
+    let mut imported = PetriNet::from_lola_string(&lola).unwrap();
+    imported.set_name_ids(true);
+    assert_eq!(imported.to_lola_string().unwrap(), lola);
+    let pnml = net.to_pnml_string().unwrap();
+    assert!(pnml.contains("<capacity>3</capacity>"));
+    let mut imported = PetriNet::from_pnml_string(&pnml).unwrap();
+    imported.set_name_ids(true);
+    assert_eq!(imported.to_pnml_string().unwrap(), pnml);
+    let overfull = pnml.replace(
+        "<capacity>3</capacity>",
+        "<capacity>3</capacity></toolspecific>\
+         <initialMarking><text>4</text></initialMarking>\
+         <toolspecific tool=\"PetriToStar\">",
+    );
+    assert!(matches!(
+        PetriNet::from_pnml_string(&overfull),
+        Err(PetriError::CorruptedData(_))
+    ));
+
+    // the clash is found before any complement is added
+    let mut clashing = imported.clone();
+    clashing.set_unique_names(true).unwrap();
+    let taken = clashing.add_transition();
+    taken
+        .name(&mut clashing, "buffer_complement".into())
+        .unwrap();
+    assert!(matches!(
+        clashing.complement_capacities(),
+        Err(PetriError::DuplicateName(name)) if name == "buffer_complement"
+    ));
+    assert!(clashing.has_capacities());
+    assert_eq!(clashing.places().count(), 2);
+    assert_eq!(clashing.arcs().count(), 4);
+
+    let limits = ExplorationLimits::default();
+    let bounded = net.reachability_graph(&limits).unwrap().markings().len();
+    assert_eq!(
+        imported
+            .reachability_graph(&limits)
+            .unwrap()
+            .markings()
+            .len(),
+        bounded
+    );
+    let complements = net.complement_capacities().unwrap();
+    net.validate().unwrap();
+    assert!(!net.has_capacities());
+    assert_eq!(complements.len(), 1);
+    let graph = net.reachability_graph(&limits).unwrap();
+    assert_eq!(graph.markings().len(), bounded);
+    assert!(graph
+        .markings()
+        .iter()
+        .all(|marking| marking.tokens(buffer) + marking.tokens(complements[&buffer]) == 3));
+}
+
+#[test]
+fn priorities() {
+    let mut net = PetriNet::new();
+    let ready = net.add_place();
+    let request = net.add_place();
+    let handled = net.add_place();
+    let work = net.add_transition();
+    let idle = net.add_transition();
+    let interrupt = net.add_transition();
+    net.add_arc(ready, work).unwrap();
+    net.add_arc(work, ready).unwrap();
+    net.add_read_arc(ready, idle, 1).unwrap();
+    net.add_arc(request, interrupt).unwrap();
+    net.add_arc(interrupt, handled).unwrap();
+    let work = TransitionRef::try_from(work).unwrap();
+    let idle = TransitionRef::try_from(idle).unwrap();
+    let interrupt = TransitionRef::try_from(interrupt).unwrap();
+    for place in &[ready, request] {
+        let place = PlaceRef::try_from(*place).unwrap();
+        place.marking(&mut net, 1).unwrap();
+    }
+    net.add_priority(interrupt, work).unwrap();
+    net.add_priority(work, idle).unwrap();
+    assert!(net.add_priority(idle, interrupt).is_err());
+    net.validate().unwrap();
+
+    // idle is blocked by interrupt through work
+    let initial = net.initial_marking();
+    let enabled: Vec<_> = net.enabled_transitions(&initial).collect();
+    assert_eq!(enabled, vec![interrupt]);
+    let graph = net
+        .reachability_graph(&ExplorationLimits::default())
+        .unwrap();
+    assert_eq!(graph.markings().len(), 2);
+    let successors: Vec<_> = graph.successors(1).map(|edge| edge.transition).collect();
+    assert_eq!(successors, vec![work]);
+
+    assert!(net
+        .to_tina_string()
+        .unwrap()
+        .ends_with("pr t_0 > t_1\npr t_2 > t_0\n"));
+    assert!(net.to_lola_string().is_err());
+    let pnml = net.to_pnml_string().unwrap();
+    let imported = PetriNet::from_pnml_string(&pnml).unwrap();
+    assert_eq!(
+        imported.priorities().collect::<Vec<_>>(),
+        net.priorities().collect::<Vec<_>>()
+    );
+    assert_eq!(imported.to_pnml_string().unwrap(), pnml);
+
+    net.remove_transition(work).unwrap();
+    assert_eq!(net.priorities().count(), 0);
+}
+
+#[test]
+fn unique_names() {
+    let mut net = PetriNet::new();
+    let first = net.add_place();
+    let second = net.add_transition();
+    first.name(&mut net, "shared".into()).unwrap();
+    second.name(&mut net, "shared".into()).unwrap();
+    assert_eq!(net.node_by_name("shared"), Some(first));
+    assert!(matches!(
+        net.set_unique_names(true),
+        Err(PetriError::DuplicateName(name)) if name == "shared"
+    ));
+    // strict mode stays off after the failure
+    let third = net.add_place();
+    third.name(&mut net, "shared".into()).unwrap();
+
+    third.name(&mut net, "third".into()).unwrap();
+    second.name(&mut net, "second".into()).unwrap();
+    net.set_unique_names(true).unwrap();
+    assert!(matches!(
+        second.name(&mut net, "shared".into()),
+        Err(PetriError::DuplicateName(name)) if name == "shared"
+    ));
+    assert_eq!(net.node_by_name("second"), Some(second));
+    assert_eq!(net.node_by_name("shared"), Some(first));
+    // a node can keep its own name
+    first.name(&mut net, "shared".into()).unwrap();
+
+    let added = net.add_transition();
+    assert!(matches!(
+        added.name(&mut net, "third".into()),
+        Err(PetriError::DuplicateName(name)) if name == "third"
+    ));
+    added.name(&mut net, "added".into()).unwrap();
+
+    // names of removed nodes are free again
+    net.remove_place(PlaceRef::try_from(third).unwrap())
+        .unwrap();
+    added.name(&mut net, "third".into()).unwrap();
+    assert_eq!(net.node_by_name("third"), Some(added));
+    net.set_unique_names(false).unwrap();
+    first.name(&mut net, "third".into()).unwrap();
+}
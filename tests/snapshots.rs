@@ -0,0 +1,102 @@
+//! Exporter output is compared byte for byte against the files in
+//! `tests/snapshots`. Intended changes of the output formats need an update of
+//! these files.
+
+mod common;
+
+use common::{build_mutex, mutex};
+use petri_to_star::ExplorationLimits;
+
+#[test]
+fn lola_snapshot() {
+    let net = mutex();
+    assert_eq!(
+        net.to_lola_string().unwrap(),
+        include_str!("snapshots/mutex.lola")
+    );
+    assert_eq!(
+        net.to_lola_string().unwrap(),
+        build_mutex(true).to_lola_string().unwrap()
+    );
+}
+
+#[test]
+fn pnml_snapshot() {
+    let net = mutex();
+    assert_eq!(
+        net.to_pnml_string().unwrap(),
+        include_str!("snapshots/mutex.pnml")
+    );
+}
+
+#[test]
+fn dot_snapshot() {
+    let net = mutex();
+    assert_eq!(
+        net.to_dot_string().unwrap(),
+        include_str!("snapshots/mutex.dot")
+    );
+}
+
+#[test]
+fn reachability_dot_snapshot() {
+    let net = mutex();
+    let graph = net
+        .reachability_graph(&ExplorationLimits::default())
+        .unwrap();
+    assert_eq!(
+        graph.to_dot_string(&net).unwrap(),
+        include_str!("snapshots/mutex_reachability.dot")
+    );
+}
+
+#[test]
+fn matrix_snapshots() {
+    let net = mutex();
+    let pre = net.pre_matrix().unwrap();
+    let post = net.post_matrix().unwrap();
+    let incidence = net.incidence_matrix().unwrap();
+    assert_eq!(
+        pre.to_matrix_market_string().unwrap(),
+        include_str!("snapshots/mutex_pre.mtx")
+    );
+    assert_eq!(
+        post.to_matrix_market_string().unwrap(),
+        include_str!("snapshots/mutex_post.mtx")
+    );
+    assert_eq!(
+        incidence.to_matrix_market_string().unwrap(),
+        include_str!("snapshots/mutex_incidence.mtx")
+    );
+    assert_eq!(
+        pre.to_csv_string().unwrap(),
+        include_str!("snapshots/mutex_pre.csv")
+    );
+    assert_eq!(
+        post.to_csv_string().unwrap(),
+        include_str!("snapshots/mutex_post.csv")
+    );
+    assert_eq!(
+        incidence.to_csv_string().unwrap(),
+        include_str!("snapshots/mutex_incidence.csv")
+    );
+}
+
+#[test]
+fn repeated_exports_are_identical() {
+    for _ in 0..10 {
+        let net = mutex();
+        assert_eq!(
+            net.to_lola_string().unwrap(),
+            mutex().to_lola_string().unwrap()
+        );
+        assert_eq!(
+            net.to_pnml_string().unwrap(),
+            net.clone().to_pnml_string().unwrap()
+        );
+        assert_eq!(
+            net.to_dot_string().unwrap(),
+            mutex().to_dot_string().unwrap()
+        );
+    }
+}
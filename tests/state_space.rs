@@ -0,0 +1,347 @@
+//! Exploration of the state space: reachability and coverability, invariants,
+//! state classes, deadlocks and liveness.
+
+mod common;
+
+use common::mutex;
+use petri_to_star::{
+    DeadlockSearch, ExplorationLimits, Liveness, NodeRef, PetriError, PetriNet, PlaceRef,
+    SearchOrder, TimeInterval, Tokens, TransitionRef,
+};
+use std::collections::HashMap;
+use std::convert::TryFrom;
+
+#[test]
+fn reachability() {
+    let net = mutex();
+    let graph = net
+        .reachability_graph(&ExplorationLimits::default())
+        .unwrap();
+    assert!(graph.is_complete());
+    assert_eq!(graph.markings().len(), 3);
+    assert_eq!(graph.edges().len(), 4);
+    assert_eq!(graph.state(&net.initial_marking()), Some(0));
+    for (state, marking) in graph.markings().iter().enumerate() {
+        assert_eq!(graph.state(marking), Some(state));
+        let mut replayed = net.initial_marking();
+        for transition in graph.path_to(state).unwrap() {
+            transition.fire(&net, &mut replayed).unwrap();
+        }
+        assert_eq!(&replayed, marking);
+    }
+    assert_eq!(graph.path_to(3), None);
+    assert!((0..3).all(|state| graph.is_explored(state)));
+
+    let limits = ExplorationLimits {
+        order: SearchOrder::DepthFirst,
+        max_states: Some(2),
+        max_memory: None,
+    };
+    let graph = net.reachability_graph(&limits).unwrap();
+    assert!(!graph.is_complete());
+    assert_eq!(graph.markings().len(), 2);
+    assert!(graph.edges().iter().all(|edge| edge.target < 2));
+    // the limit was hit while the successors of the initial marking were added
+    assert!(!graph.is_explored(0));
+
+    // the initial marking is always stored
+    let limits = ExplorationLimits {
+        max_memory: Some(1),
+        ..ExplorationLimits::default()
+    };
+    let graph = net.reachability_graph(&limits).unwrap();
+    assert!(!graph.is_complete());
+    assert_eq!(graph.markings().len(), 1);
+    assert!(graph.edges().is_empty());
+}
+
+#[test]
+fn coverability() {
+    // `produce` can fire forever and fills the buffer, `stop` ends the run
+    let mut net = PetriNet::new();
+    let ready = net.add_place();
+    let buffer = net.add_place();
+    let done = net.add_place();
+    let produce = net.add_transition();
+    let stop = net.add_transition();
+    net.add_arc(ready, produce).unwrap();
+    net.add_arc(produce, ready).unwrap();
+    net.add_arc(produce, buffer).unwrap();
+    net.add_arc(ready, stop).unwrap();
+    net.add_arc(stop, done).unwrap();
+    let ready = PlaceRef::try_from(ready).unwrap();
+    let buffer = PlaceRef::try_from(buffer).unwrap();
+    let done = PlaceRef::try_from(done).unwrap();
+    ready.marking(&mut net, 1).unwrap();
+
+    let tree = net.coverability_tree().unwrap();
+    assert!(!tree.is_bounded());
+    assert!(tree
+        .markings()
+        .any(|marking| marking.tokens(buffer) == Tokens::Omega));
+    assert!(tree.markings().all(|marking| {
+        marking.tokens(ready) != Tokens::Omega && marking.tokens(done) != Tokens::Omega
+    }));
+    assert_eq!(tree.bound(ready), Some(1));
+    assert_eq!(tree.bound(done), Some(1));
+    assert_eq!(tree.bound(buffer), None);
+    assert_eq!(tree.unbounded_places(), vec![buffer]);
+    assert_eq!(tree.unbounded_witness(ready), None);
+
+    let witness = tree.unbounded_witness(buffer).unwrap();
+    assert!(!witness.pump.is_empty());
+    let mut marking = net.initial_marking();
+    for transition in &witness.prefix {
+        transition.fire(&net, &mut marking).unwrap();
+    }
+    for _ in 0..3 {
+        let before = marking.clone();
+        for transition in &witness.pump {
+            transition.fire(&net, &mut marking).unwrap();
+        }
+        assert!(net
+            .places()
+            .all(|place| marking.tokens(place) >= before.tokens(place)));
+        assert!(marking.tokens(buffer) > before.tokens(buffer));
+    }
+
+    assert!(mutex().coverability_tree().unwrap().is_bounded());
+}
+
+#[test]
+fn invariants() {
+    let net = mutex();
+    let place = |name: &str| net.place_by_name(name).unwrap();
+    let transition = |name: &str| net.transition_by_name(name).unwrap();
+    let mut expected: Vec<HashMap<_, _>> = vec![
+        vec![(place("idle_0"), 1), (place("critical_0"), 1)],
+        vec![(place("idle_1"), 1), (place("critical_1"), 1)],
+        vec![
+            (place("resource"), 1),
+            (place("critical_0"), 1),
+            (place("critical_1"), 1),
+        ],
+    ]
+    .into_iter()
+    .map(|weights| weights.into_iter().collect())
+    .collect();
+    let mut invariants = net.p_invariants().unwrap();
+    let key = |invariant: &HashMap<PlaceRef, i64>| {
+        (invariant.len(), invariant.contains_key(&place("idle_1")))
+    };
+    invariants.sort_by_key(key);
+    expected.sort_by_key(key);
+    assert_eq!(invariants, expected);
+    for invariant in &invariants {
+        assert!(net.is_p_invariant(invariant).unwrap());
+    }
+    assert!(!net
+        .is_p_invariant(&vec![(place("resource"), 1)].into_iter().collect())
+        .unwrap());
+
+    let mut invariants = net.t_invariants().unwrap();
+    invariants.sort_by_key(|invariant| invariant.contains_key(&transition("enter_1")));
+    let expected: Vec<HashMap<_, _>> = vec![
+        vec![(transition("enter_0"), 1), (transition("leave_0"), 1)],
+        vec![(transition("enter_1"), 1), (transition("leave_1"), 1)],
+    ]
+    .into_iter()
+    .map(|weights| weights.into_iter().collect())
+    .collect();
+    assert_eq!(invariants, expected);
+    for invariant in &invariants {
+        assert!(net.is_t_invariant(invariant).unwrap());
+    }
+
+    // a transition without preset only ever adds tokens
+    let mut net = PetriNet::new();
+    let place = net.add_place();
+    let source = net.add_transition();
+    net.add_arc(source, place).unwrap();
+    assert!(net.p_invariants().unwrap().is_empty());
+    assert!(net.t_invariants().unwrap().is_empty());
+
+    // combining the rows of the two places overflows
+    let mut net = PetriNet::new();
+    let first = net.add_place();
+    let second = net.add_place();
+    let take = net.add_transition();
+    let give = net.add_transition();
+    let weight = 1 << 62;
+    net.add_weighted_arc(second, take, weight).unwrap();
+    net.add_weighted_arc(take, first, weight).unwrap();
+    net.add_weighted_arc(give, first, weight).unwrap();
+    assert!(matches!(
+        net.p_invariants(),
+        Err(PetriError::InvalidData(_))
+    ));
+    let first = PlaceRef::try_from(first).unwrap();
+    assert!(matches!(
+        net.is_p_invariant(&vec![(first, i64::MAX)].into_iter().collect()),
+        Err(PetriError::InvalidData(_))
+    ));
+}
+
+#[test]
+fn time_intervals() {
+    let mut net = PetriNet::new();
+    let waiting = net.add_place();
+    let answered = net.add_place();
+    let timed_out = net.add_place();
+    let answer = net.add_transition();
+    let timeout = net.add_transition();
+    net.add_arc(waiting, answer).unwrap();
+    net.add_arc(answer, answered).unwrap();
+    net.add_arc(waiting, timeout).unwrap();
+    net.add_weighted_arc(timeout, timed_out, 2).unwrap();
+    PlaceRef::try_from(waiting)
+        .unwrap()
+        .marking(&mut net, 1)
+        .unwrap();
+    let answer = TransitionRef::try_from(answer).unwrap();
+    let timeout = TransitionRef::try_from(timeout).unwrap();
+    let interval = TimeInterval::new(1, Some(2)).unwrap();
+    answer.interval(&mut net, Some(interval)).unwrap();
+    timeout
+        .interval(&mut net, Some(TimeInterval::new(3, None).unwrap()))
+        .unwrap();
+    assert!(TimeInterval::new(3, Some(2)).is_err());
+
+    // the answer always arrives before the timeout
+    let graph = net
+        .state_class_graph(&ExplorationLimits::default())
+        .unwrap();
+    assert!(graph.is_complete());
+    assert_eq!(graph.classes().len(), 2);
+    assert_eq!(graph.classes()[0].firing_interval(answer), Some(interval));
+    let edges: Vec<_> = graph.edges().iter().map(|edge| edge.transition).collect();
+    assert_eq!(edges, vec![answer]);
+
+    assert_eq!(
+        net.to_tina_string().unwrap(),
+        "net petrinet\npl p_0 (1)\npl p_1\npl p_2\ntr t_0 [1,2] p_0 -> p_1\ntr t_1 [3,w[ p_0 -> p_2*2\n"
+    );
+
+    let pnml = net.to_pnml_string().unwrap();
+    assert!(pnml.contains("<interval earliest=\"1\" latest=\"2\" />"));
+    assert!(pnml.contains("<interval earliest=\"3\" />"));
+    let imported = PetriNet::from_pnml_string(&pnml).unwrap();
+    assert_eq!(
+        imported.to_tina_string().unwrap(),
+        net.to_tina_string().unwrap()
+    );
+    let empty = pnml.replace("latest=\"2\"", "latest=\"0\"");
+    assert!(matches!(
+        PetriNet::from_pnml_string(&empty),
+        Err(PetriError::CorruptedData(_))
+    ));
+}
+
+#[test]
+fn deadlocks() {
+    assert_eq!(
+        mutex()
+            .find_deadlock(&ExplorationLimits::default())
+            .unwrap(),
+        DeadlockSearch::DeadlockFree
+    );
+
+    // two processes that take the same locks in opposite order
+    let mut net = PetriNet::new();
+    let locks = [net.add_place(), net.add_place()];
+    for lock in &locks {
+        PlaceRef::try_from(*lock)
+            .unwrap()
+            .marking(&mut net, 1)
+            .unwrap();
+    }
+    for (process, order) in [("p", [0, 1]), ("q", [1, 0])].iter() {
+        let start = net.add_place();
+        let holding = net.add_place();
+        PlaceRef::try_from(start)
+            .unwrap()
+            .marking(&mut net, 1)
+            .unwrap();
+        let first = net.add_transition();
+        let second = net.add_transition();
+        first
+            .name(&mut net, format!("{}_takes_{}", process, order[0]))
+            .unwrap();
+        second
+            .name(&mut net, format!("{}_takes_{}", process, order[1]))
+            .unwrap();
+        net.add_arc(start, first).unwrap();
+        net.add_arc(locks[order[0]], first).unwrap();
+        net.add_arc(first, holding).unwrap();
+        net.add_arc(holding, second).unwrap();
+        net.add_arc(locks[order[1]], second).unwrap();
+        net.add_arc(second, locks[order[0]]).unwrap();
+        net.add_arc(second, locks[order[1]]).unwrap();
+        net.add_arc(second, start).unwrap();
+    }
+    let deadlock = match net.find_deadlock(&ExplorationLimits::default()).unwrap() {
+        DeadlockSearch::Found(deadlock) => deadlock,
+        other => panic!("no deadlock found: {:?}", other),
+    };
+    assert_eq!(deadlock.display(&net).to_string(), "p_takes_0 q_takes_1");
+    assert_eq!(net.enabled_transitions(deadlock.marking()).count(), 0);
+    let limits = ExplorationLimits {
+        max_states: Some(2),
+        ..ExplorationLimits::default()
+    };
+    assert_eq!(
+        net.find_deadlock(&limits).unwrap(),
+        DeadlockSearch::NotFoundWithinLimits
+    );
+    // the order of the limits does not change the shortest sequence
+    let limits = ExplorationLimits {
+        order: SearchOrder::DepthFirst,
+        ..ExplorationLimits::default()
+    };
+    assert_eq!(
+        net.find_deadlock(&limits).unwrap(),
+        DeadlockSearch::Found(deadlock)
+    );
+}
+
+#[test]
+fn liveness() {
+    let mut net = mutex();
+    let critical_0 = net.place_by_name("critical_0").unwrap();
+    let critical_1 = net.place_by_name("critical_1").unwrap();
+    // both processes are never critical at the same time
+    let collide = net.add_transition();
+    net.add_arc(NodeRef::Place(critical_0), collide).unwrap();
+    net.add_arc(NodeRef::Place(critical_1), collide).unwrap();
+    // fires once at the beginning
+    let boot = net.add_place();
+    let booted = net.add_place();
+    let start = net.add_transition();
+    net.add_arc(boot, start).unwrap();
+    net.add_arc(start, booted).unwrap();
+    PlaceRef::try_from(boot)
+        .unwrap()
+        .marking(&mut net, 1)
+        .unwrap();
+
+    let limits = ExplorationLimits::default();
+    let report = net.liveness(&limits).unwrap();
+    let enter = net.transition_by_name("enter_0").unwrap();
+    assert_eq!(report[&enter], Liveness::Live);
+    assert_eq!(
+        report[&TransitionRef::try_from(collide).unwrap()],
+        Liveness::Dead
+    );
+    assert_eq!(
+        report[&TransitionRef::try_from(start).unwrap()],
+        Liveness::QuasiLive
+    );
+    assert_eq!(report.len(), 6);
+
+    let graph = net.reachability_graph(&limits).unwrap();
+    let components = graph.strongly_connected_components();
+    assert_eq!(components.len(), 2);
+    assert_eq!(components.iter().map(Vec::len).sum::<usize>(), 6);
+    // the initial marking is left for good by the first firing of start
+    assert_eq!(components[1], vec![0, 1, 2]);
+}
@@ -0,0 +1,261 @@
+//! Stochastic nets: simulation and the derived Markov chains.
+
+use petri_to_star::{
+    ExplorationLimits, PetriError, PetriNet, PlaceRef, SimulationLimits, StochasticKind,
+    TransitionRef,
+};
+use std::convert::TryFrom;
+
+#[test]
+fn stochastic_simulation() {
+    // M/M/1 queue with an immediate routing decision in front
+    let mut net = PetriNet::new();
+    let source = net.add_place();
+    let incoming = net.add_place();
+    let queue = net.add_place();
+    let rejected = net.add_place();
+    let arrive = net.add_transition();
+    let accept = net.add_transition();
+    let reject = net.add_transition();
+    let serve = net.add_transition();
+    net.add_arc(source, arrive).unwrap();
+    net.add_arc(arrive, source).unwrap();
+    net.add_arc(arrive, incoming).unwrap();
+    net.add_arc(incoming, accept).unwrap();
+    net.add_arc(accept, queue).unwrap();
+    net.add_arc(incoming, reject).unwrap();
+    net.add_arc(reject, rejected).unwrap();
+    net.add_arc(queue, serve).unwrap();
+    PlaceRef::try_from(source)
+        .unwrap()
+        .marking(&mut net, 1)
+        .unwrap();
+    let kinds = [
+        (arrive, StochasticKind::Exponential { rate: 4.0 / 3.0 }),
+        (accept, StochasticKind::Immediate { weight: 3.0 }),
+        (reject, StochasticKind::Immediate { weight: 1.0 }),
+        (serve, StochasticKind::Exponential { rate: 2.0 }),
+    ];
+    let limits = SimulationLimits {
+        end_time: Some(20000.0),
+        max_steps: None,
+    };
+    assert!(net.simulate(&limits, 7).is_err());
+    for (transition, kind) in &kinds {
+        let transition = TransitionRef::try_from(*transition).unwrap();
+        transition.stochastic(&mut net, Some(*kind)).unwrap();
+    }
+    let trajectory = net.simulate(&limits, 7).unwrap();
+    assert_eq!(trajectory, net.simulate(&limits, 7).unwrap());
+    // accepted customers arrive with rate 1, utilization 0.5
+    let serve = TransitionRef::try_from(serve).unwrap();
+    let throughput = trajectory.throughput(serve);
+    assert!((throughput - 1.0).abs() < 0.05, "throughput {}", throughput);
+    let queue = PlaceRef::try_from(queue).unwrap();
+    let mean = trajectory.mean_tokens(queue);
+    assert!((mean - 1.0).abs() < 0.1, "mean tokens {}", mean);
+    let incoming = PlaceRef::try_from(incoming).unwrap();
+    assert!(trajectory.mean_tokens(incoming) == 0.0);
+    assert_eq!(trajectory.end_time(), 20000.0);
+
+    // the run ends at the last step, not at the end time of the limits
+    let limits = SimulationLimits {
+        end_time: Some(20000.0),
+        max_steps: Some(10),
+    };
+    let trajectory = net.simulate(&limits, 7).unwrap();
+    assert_eq!(trajectory.steps().len(), 10);
+    let last = trajectory.steps().last().unwrap().time;
+    assert_eq!(trajectory.end_time(), last);
+    assert!(last < 20000.0);
+    let served = trajectory
+        .steps()
+        .iter()
+        .filter(|step| step.transition == serve)
+        .count();
+    assert_eq!(trajectory.throughput(serve), served as f64 / last);
+
+    // a dead marking lasts until the end time of the limits
+    let mut net = PetriNet::new();
+    let start = net.add_place();
+    let done = net.add_place();
+    let finish = net.add_transition();
+    net.add_arc(start, finish).unwrap();
+    net.add_arc(finish, done).unwrap();
+    PlaceRef::try_from(start)
+        .unwrap()
+        .marking(&mut net, 1)
+        .unwrap();
+    TransitionRef::try_from(finish)
+        .unwrap()
+        .stochastic(&mut net, Some(StochasticKind::Exponential { rate: 1.0 }))
+        .unwrap();
+    let limits = SimulationLimits {
+        end_time: Some(100.0),
+        max_steps: None,
+    };
+    let trajectory = net.simulate(&limits, 7).unwrap();
+    let finished = trajectory.steps()[0].time;
+    assert!(finished < 100.0);
+    assert_eq!(trajectory.end_time(), 100.0);
+    let done = PlaceRef::try_from(done).unwrap();
+    let mean = trajectory.mean_tokens(done);
+    assert!((mean - (100.0 - finished) / 100.0).abs() < 1e-12);
+}
+
+#[test]
+fn immediate_loops() {
+    let mut net = PetriNet::new();
+    let ping = net.add_place();
+    let pong = net.add_place();
+    let done = net.add_place();
+    let there = net.add_transition();
+    let back = net.add_transition();
+    let finish = net.add_transition();
+    net.add_arc(ping, there).unwrap();
+    net.add_arc(there, pong).unwrap();
+    net.add_arc(pong, back).unwrap();
+    net.add_arc(back, ping).unwrap();
+    net.add_arc(ping, finish).unwrap();
+    net.add_arc(finish, done).unwrap();
+    PlaceRef::try_from(ping)
+        .unwrap()
+        .marking(&mut net, 1)
+        .unwrap();
+    let [there, back, finish] =
+        [there, back, finish].map(|transition| TransitionRef::try_from(transition).unwrap());
+    for transition in &[there, back] {
+        transition
+            .stochastic(&mut net, Some(StochasticKind::Immediate { weight: 1.0 }))
+            .unwrap();
+    }
+    finish
+        .stochastic(&mut net, Some(StochasticKind::Exponential { rate: 1.0 }))
+        .unwrap();
+    let limits = SimulationLimits {
+        end_time: Some(10.0),
+        max_steps: None,
+    };
+    assert!(matches!(
+        net.simulate(&limits, 3),
+        Err(PetriError::UnsupportedFeature(_))
+    ));
+    assert!(matches!(
+        net.ctmc(&ExplorationLimits::default()),
+        Err(PetriError::UnsupportedFeature(_))
+    ));
+
+    // an immediate exit ends the loop, the dead marking ends the run
+    finish
+        .stochastic(&mut net, Some(StochasticKind::Immediate { weight: 0.1 }))
+        .unwrap();
+    let trajectory = net.simulate(&limits, 3).unwrap();
+    assert_eq!(trajectory.steps().last().unwrap().transition, finish);
+    assert_eq!(trajectory.end_time(), 10.0);
+    let ctmc = net.ctmc(&ExplorationLimits::default()).unwrap();
+    assert_eq!(ctmc.markings().len(), 1);
+    assert!((ctmc.initial_distribution()[0] - 1.0).abs() < 1e-12);
+}
+
+#[test]
+fn markov_chain() {
+    // a job is started and then either kept (1) or dropped (3) immediately
+    let mut net = PetriNet::new();
+    let idle = net.add_place();
+    let choice = net.add_place();
+    let busy = net.add_place();
+    let start = net.add_transition();
+    let keep = net.add_transition();
+    let drop = net.add_transition();
+    let finish = net.add_transition();
+    net.add_arc(idle, start).unwrap();
+    net.add_arc(start, choice).unwrap();
+    net.add_arc(choice, keep).unwrap();
+    net.add_arc(keep, busy).unwrap();
+    net.add_arc(choice, drop).unwrap();
+    net.add_arc(drop, idle).unwrap();
+    net.add_arc(busy, finish).unwrap();
+    net.add_arc(finish, idle).unwrap();
+    PlaceRef::try_from(idle)
+        .unwrap()
+        .marking(&mut net, 1)
+        .unwrap();
+    let kinds = [
+        (start, StochasticKind::Exponential { rate: 1.0 }),
+        (keep, StochasticKind::Immediate { weight: 1.0 }),
+        (drop, StochasticKind::Immediate { weight: 3.0 }),
+        (finish, StochasticKind::Exponential { rate: 2.0 }),
+    ];
+    for (transition, kind) in &kinds {
+        let transition = TransitionRef::try_from(*transition).unwrap();
+        transition.stochastic(&mut net, Some(*kind)).unwrap();
+    }
+    let ctmc = net.ctmc(&ExplorationLimits::default()).unwrap();
+    assert_eq!(ctmc.markings().len(), 2);
+    assert_eq!(
+        ctmc.to_matrix_market_string().unwrap(),
+        "%%MatrixMarket matrix coordinate real general\n2 2 4\n1 1 -0.25\n1 2 0.25\n2 1 2\n2 2 -2\n"
+    );
+    let distribution = ctmc.steady_state(1e-12, 100_000).unwrap();
+    let busy = PlaceRef::try_from(busy).unwrap();
+    assert!((ctmc.mean_tokens(&distribution, busy) - 1.0 / 9.0).abs() < 1e-9);
+    let start = TransitionRef::try_from(start).unwrap();
+    let throughput = ctmc.throughput(&distribution, start).unwrap();
+    assert!((throughput - 8.0 / 9.0).abs() < 1e-9);
+    let keep = TransitionRef::try_from(keep).unwrap();
+    assert!(ctmc.throughput(&distribution, keep).is_err());
+
+    let pnml = net.to_pnml_string().unwrap();
+    assert!(pnml.contains("<rate>2</rate>"));
+    assert!(pnml.contains("<weight>3</weight>"));
+    let imported = PetriNet::from_pnml_string(&pnml).unwrap();
+    assert_eq!(
+        imported
+            .ctmc(&ExplorationLimits::default())
+            .unwrap()
+            .to_matrix_market_string()
+            .unwrap(),
+        ctmc.to_matrix_market_string().unwrap()
+    );
+    assert!(matches!(
+        PetriNet::from_pnml_string(&pnml.replace("<rate>2</rate>", "<rate>0</rate>")),
+        Err(PetriError::CorruptedData(_))
+    ));
+    assert!(matches!(
+        PetriNet::from_pnml_string(
+            &pnml.replace("<rate>2</rate>", "<rate>2</rate><weight>1</weight>")
+        ),
+        Err(PetriError::CorruptedData(_))
+    ));
+}
+
+#[test]
+fn immediate_priority() {
+    // every produced token is consumed before the producer can fire again
+    let mut net = PetriNet::new();
+    let buffer = net.add_place();
+    let produce = net.add_transition();
+    let consume = net.add_transition();
+    net.add_arc(produce, buffer).unwrap();
+    net.add_arc(buffer, consume).unwrap();
+    let produce = TransitionRef::try_from(produce).unwrap();
+    let consume = TransitionRef::try_from(consume).unwrap();
+    produce
+        .stochastic(&mut net, Some(StochasticKind::Exponential { rate: 1.5 }))
+        .unwrap();
+    consume
+        .stochastic(&mut net, Some(StochasticKind::Immediate { weight: 1.0 }))
+        .unwrap();
+    let limits = ExplorationLimits {
+        max_states: Some(10),
+        ..ExplorationLimits::default()
+    };
+    // without priorities the buffer is unbounded
+    assert!(!net.reachability_graph(&limits).unwrap().is_complete());
+    let ctmc = net.ctmc(&limits).unwrap();
+    let buffer = PlaceRef::try_from(buffer).unwrap();
+    assert_eq!(ctmc.markings().len(), 1);
+    assert_eq!(ctmc.markings()[0].tokens(buffer), 0);
+    let distribution = ctmc.steady_state(1e-12, 1_000).unwrap();
+    assert_eq!(ctmc.throughput(&distribution, produce).unwrap(), 1.5);
+}